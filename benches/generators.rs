@@ -0,0 +1,79 @@
+//! Compares the generators on random soups of increasing size. The soups are
+//! aged first, so that like a real run most of the board has settled down.
+//! The speedup is the bitwise generator's over the simple one.
+//!
+//! Run with `cargo bench`.
+
+extern crate life;
+
+use std::time::{Duration, Instant};
+
+use life::board::bit::BitBoard;
+use life::board::grid::GridBoard;
+use life::board::Topology;
+use life::generator::{ActiveRegionGenerator, BitwiseGenerator, SimpleGenerator};
+use life::interface::{Board, Generator};
+
+const SEED: u64 = 1;
+const DENSITY: f64 = 0.5;
+const AGE: u32 = 2000;
+
+/// The average time to generate one generation, once the runs add up to at
+/// least `budget`.
+fn time_per_generation<T, G>(generator: &G, board: T, budget: Duration) -> Duration
+where
+    T: Board,
+    G: Generator<T>,
+{
+    let mut board = board;
+    let mut generations = 0;
+    let start = Instant::now();
+    while generations == 0 || start.elapsed() < budget {
+        board = generator.generate(&board);
+        generations += 1;
+    }
+    start.elapsed() / generations
+}
+
+fn main() {
+    let budget = Duration::from_secs(1);
+    let simple = SimpleGenerator::new();
+    let active = ActiveRegionGenerator::new();
+    let bitwise = BitwiseGenerator::new();
+
+    println!(
+        "{:>11} {:>14} {:>14} {:>14} {:>9}",
+        "board", "simple", "active", "bitwise", "speedup"
+    );
+    for &size in &[64, 256, 1024] {
+        let mut bits =
+            BitBoard::seeded_random(size, size, SEED, DENSITY).with_topology(Topology::Toroidal);
+        for _ in 0..AGE {
+            bits = bitwise.generate(&bits);
+        }
+        let grid = GridBoard::from(bits.rows().clone()).with_topology(Topology::Toroidal);
+
+        let simple_time = time_per_generation(&simple, grid.clone(), budget);
+        let active_time = time_per_generation(&active, grid, budget);
+        let bitwise_time = time_per_generation(&bitwise, bits, budget);
+
+        println!(
+            "{:>11} {:>14?} {:>14?} {:>14?} {:>8.0}x",
+            format!("{0}x{0}", size),
+            simple_time,
+            active_time,
+            bitwise_time,
+            simple_time.as_secs_f64() / bitwise_time.as_secs_f64()
+        );
+    }
+
+    let size = 10_000;
+    let bits = BitBoard::seeded_random(size, size, SEED, DENSITY).with_topology(Topology::Toroidal);
+    println!(
+        "{:>11} {:>14} {:>14} {:>14?}",
+        format!("{0}x{0}", size),
+        "-",
+        "-",
+        time_per_generation(&bitwise, bits, budget)
+    );
+}
@@ -0,0 +1,522 @@
+use std::sync::OnceLock;
+
+use interface::{Board, Rows};
+use models::*;
+use board::symmetry::Symmetry;
+use board::{sow, Topology};
+
+/// A bounded board that packs each row into 64 bit words, one bit per cell,
+/// so large boards take an eighth of a byte per cell rather than a whole
+/// `Cell`.
+///
+/// `alive` holds a set bit for every living cell and `changed` a set bit for
+/// every cell that was born or died in the last generation: a cell that is
+/// alive and changed was born, one that is dead and changed died. Bits past
+/// the end of a row are always clear.
+///
+/// The `Rows` that `Board::rows` needs are only built the first time they're
+/// asked for, and then take a whole `Cell` per cell again. Every other
+/// `Board` method works on the words directly, so only code that really
+/// wants the dense rows, like the renderers and `SimpleGenerator`, pays for
+/// them.
+#[derive(Debug, Clone)]
+pub struct BitBoard {
+    width: i32,
+    height: i32,
+    topology: Topology,
+    words_per_row: usize,
+    alive: Vec<u64>,
+    changed: Vec<u64>,
+    rows: OnceLock<Rows>,
+}
+
+impl BitBoard {
+    /// A board of dead cells.
+    pub fn new(width: i32, height: i32) -> BitBoard {
+        let words_per_row = words_for(width);
+        let words = words_per_row * height.max(0) as usize;
+        BitBoard {
+            width,
+            height,
+            topology: Topology::Bounded,
+            words_per_row,
+            alive: vec![0; words],
+            changed: vec![0; words],
+            rows: OnceLock::new(),
+        }
+    }
+
+    pub fn with_topology(self, topology: Topology) -> BitBoard {
+        BitBoard { topology, ..self }
+    }
+
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+
+    pub fn height(&self) -> i32 {
+        self.height
+    }
+
+    /// The number of words that each row is packed into.
+    pub fn words_per_row(&self) -> usize {
+        self.words_per_row
+    }
+
+    /// The living cells of row `y`, with column `x` in bit `x % 64` of word
+    /// `x / 64`.
+    pub fn alive_words(&self, y: usize) -> &[u64] {
+        &self.alive[y * self.words_per_row..(y + 1) * self.words_per_row]
+    }
+
+    /// The cells of row `y` that were born or died, laid out like
+    /// `alive_words`.
+    pub fn changed_words(&self, y: usize) -> &[u64] {
+        &self.changed[y * self.words_per_row..(y + 1) * self.words_per_row]
+    }
+
+    /// A reproducible random board, where each cell is alive with probability
+    /// `density`. Draws the same cells as `GridBoard::seeded_random`.
+    pub fn seeded_random(width: i32, height: i32, seed: u64, density: f64) -> BitBoard {
+        BitBoard::symmetric_random(width, height, seed, density, Symmetry::C1)
+    }
+
+    /// A reproducible random soup with the given symmetry. See
+    /// `symmetry::soup`.
+    pub fn symmetric_random(
+        width: i32,
+        height: i32,
+        seed: u64,
+        density: f64,
+        symmetry: Symmetry,
+    ) -> BitBoard {
+        sow(BitBoard::new(width, height), width, height, seed, density, symmetry)
+    }
+
+    pub fn fill_with(
+        width: i32,
+        height: i32,
+        mut state_for_coords: Box<dyn FnMut(Coordinates) -> CellState>,
+    ) -> BitBoard {
+        let mut board = BitBoard::new(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                let state = state_for_coords(Coordinates { x, y });
+                board.store(x as usize, y as usize, state);
+            }
+        }
+        board
+    }
+
+    /// Builds a board from rows packed the same way as `alive_words` and
+    /// `changed_words`.
+    ///
+    /// Panics if either mask isn't `height` rows of the right number of
+    /// words.
+    pub fn with_words(width: i32, height: i32, alive: Vec<u64>, changed: Vec<u64>) -> BitBoard {
+        let mut board = BitBoard::new(width, height);
+        assert_eq!(alive.len(), board.alive.len(), "wrong number of words");
+        assert_eq!(changed.len(), board.changed.len(), "wrong number of words");
+        board.alive = alive;
+        board.changed = changed;
+        board.clear_padding();
+        board
+    }
+}
+
+impl BitBoard {
+    /// Finds the row and column of the cell at `coordinates`, wrapping around
+    /// the edges of a toroidal board.
+    fn index_of(&self, coordinates: Coordinates) -> Option<(usize, usize)> {
+        let coordinates = match self.topology {
+            Topology::Toroidal if self.width > 0 && self.height > 0 => Coordinates {
+                x: coordinates.x.rem_euclid(self.width),
+                y: coordinates.y.rem_euclid(self.height),
+            },
+            _ => coordinates,
+        };
+
+        if coordinates.x >= 0
+            && coordinates.y >= 0
+            && coordinates.y < self.height
+            && coordinates.x < self.width
+        {
+            return Some((coordinates.x as usize, coordinates.y as usize));
+        }
+        None
+    }
+
+    fn state(&self, x: usize, y: usize) -> CellState {
+        let word = y * self.words_per_row + x / 64;
+        let bit = 1 << (x % 64);
+        match (self.alive[word] & bit != 0, self.changed[word] & bit != 0) {
+            (true, false) => CellState::Alive,
+            (true, true) => CellState::Born,
+            (false, true) => CellState::Died,
+            (false, false) => CellState::Dead,
+        }
+    }
+
+    fn store(&mut self, x: usize, y: usize, cell_state: CellState) {
+        let word = y * self.words_per_row + x / 64;
+        let bit = 1 << (x % 64);
+        let (alive, changed) = match cell_state {
+            CellState::Alive => (true, false),
+            CellState::Born => (true, true),
+            CellState::Died => (false, true),
+            CellState::Dead => (false, false),
+        };
+        set_bit(&mut self.alive[word], bit, alive);
+        set_bit(&mut self.changed[word], bit, changed);
+    }
+
+    /// Clears the bits past the end of each row.
+    fn clear_padding(&mut self) {
+        let padding = self.width.max(0) as usize % 64;
+        if padding == 0 {
+            return;
+        }
+        let mask = (1 << padding) - 1;
+        for y in 0..self.height.max(0) as usize {
+            let last = (y + 1) * self.words_per_row - 1;
+            self.alive[last] &= mask;
+            self.changed[last] &= mask;
+        }
+    }
+}
+
+fn words_for(width: i32) -> usize {
+    (width.max(0) as usize).div_ceil(64)
+}
+
+fn set_bit(word: &mut u64, bit: u64, value: bool) {
+    if value {
+        *word |= bit;
+    } else {
+        *word &= !bit;
+    }
+}
+
+impl PartialEq for BitBoard {
+    fn eq(&self, other: &BitBoard) -> bool {
+        self.width == other.width
+            && self.height == other.height
+            && self.topology == other.topology
+            && self.alive == other.alive
+            && self.changed == other.changed
+    }
+}
+
+impl Eq for BitBoard {}
+
+impl Board for BitBoard {
+    fn at(&self, coordinates: Coordinates) -> Option<Cell> {
+        self.index_of(coordinates).map(|(x, y)| Cell {
+            cell_state: self.state(x, y),
+            location: Coordinates {
+                x: x as i32,
+                y: y as i32,
+            },
+        })
+    }
+
+    fn rows(&self) -> &Rows {
+        self.rows.get_or_init(|| {
+            (0..self.height as usize)
+                .map(|y| {
+                    (0..self.width as usize)
+                        .map(|x| Cell {
+                            cell_state: self.state(x, y),
+                            location: Coordinates {
+                                x: x as i32,
+                                y: y as i32,
+                            },
+                        })
+                        .collect()
+                })
+                .collect()
+        })
+    }
+
+    fn set(&mut self, coordinates: Coordinates, cell_state: CellState) {
+        if let Some((x, y)) = self.index_of(coordinates) {
+            self.store(x, y, cell_state);
+            self.rows.take();
+        }
+    }
+
+    fn live_cells<'a>(&'a self) -> Box<dyn Iterator<Item = Coordinates> + 'a> {
+        let words_per_row = self.words_per_row;
+        Box::new(
+            self.alive
+                .iter()
+                .enumerate()
+                .flat_map(move |(index, &word)| {
+                    let y = (index / words_per_row) as i32;
+                    let x = ((index % words_per_row) * 64) as i32;
+                    Bits(word).map(move |bit| Coordinates { x: x + bit, y })
+                }),
+        )
+    }
+
+    fn population(&self) -> usize {
+        self.alive.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    fn changed_cells<'a>(&'a self) -> Box<dyn Iterator<Item = Cell> + 'a> {
+        let words_per_row = self.words_per_row;
+        Box::new(
+            self.changed
+                .iter()
+                .enumerate()
+                .flat_map(move |(index, &word)| {
+                    let y = index / words_per_row;
+                    let x = (index % words_per_row) * 64;
+                    Bits(word).map(move |bit| {
+                        let x = x + bit as usize;
+                        Cell {
+                            cell_state: self.state(x, y),
+                            location: Coordinates {
+                                x: x as i32,
+                                y: y as i32,
+                            },
+                        }
+                    })
+                }),
+        )
+    }
+
+    fn clear(&mut self) {
+        for word in self.alive.iter_mut().chain(self.changed.iter_mut()) {
+            *word = 0;
+        }
+        self.rows.take();
+    }
+
+    fn size(&self) -> Option<(i32, i32)> {
+        Some((self.width, self.height))
+    }
+
+    fn torus_size(&self) -> Option<(i32, i32)> {
+        match self.topology {
+            Topology::Toroidal => Some((self.width, self.height)),
+            Topology::Bounded => None,
+        }
+    }
+
+    fn successor(&self, rows: Rows) -> BitBoard {
+        BitBoard::from(rows).with_topology(self.topology)
+    }
+}
+
+/// The positions of the set bits in a word, lowest first.
+struct Bits(u64);
+
+impl Iterator for Bits {
+    type Item = i32;
+
+    fn next(&mut self) -> Option<i32> {
+        if self.0 == 0 {
+            return None;
+        }
+        let bit = self.0.trailing_zeros();
+        self.0 &= self.0 - 1;
+        Some(bit as i32)
+    }
+}
+
+impl From<Rows> for BitBoard {
+    fn from(rows: Rows) -> BitBoard {
+        let width = rows.first().map_or(0, |row| row.len()) as i32;
+        let mut board = BitBoard::new(width, rows.len() as i32);
+        for (y, row) in rows.iter().enumerate() {
+            for (x, cell) in row.iter().enumerate().take(width as usize) {
+                board.store(x, y, cell.cell_state);
+            }
+        }
+        board
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use board::grid::GridBoard;
+
+    #[test]
+    fn converts_to_and_from_rows() {
+        let grid = GridBoard::seeded_random(130, 7, 3, 0.5);
+
+        let board = BitBoard::from(grid.rows.clone());
+
+        assert_eq!(board.words_per_row(), 3);
+        assert_eq!(board.rows(), &grid.rows);
+        assert_eq!(board, BitBoard::seeded_random(130, 7, 3, 0.5));
+    }
+
+    #[test]
+    fn stores_every_cell_state() {
+        let mut board = BitBoard::new(70, 2);
+        let states = [
+            CellState::Alive,
+            CellState::Born,
+            CellState::Died,
+            CellState::Dead,
+        ];
+
+        for (i, &state) in states.iter().enumerate() {
+            board.set(Coordinates { x: 64 + i as i32, y: 1 }, state);
+        }
+
+        for (i, &state) in states.iter().enumerate() {
+            let location = Coordinates { x: 64 + i as i32, y: 1 };
+            assert_eq!(board.at(location).map(|c| c.cell_state), Some(state));
+        }
+        assert_eq!(board.changed_words(1), &[0, 0b0110]);
+        assert_eq!(board.alive_words(1), &[0, 0b0011]);
+    }
+
+    #[test]
+    fn setting_a_cell_updates_the_rows() {
+        let mut board = BitBoard::new(3, 3);
+        assert_eq!(board.rows()[1][1].cell_state, CellState::Dead);
+
+        board.set(Coordinates { x: 1, y: 1 }, CellState::Alive);
+
+        assert_eq!(board.rows()[1][1].cell_state, CellState::Alive);
+    }
+
+    #[test]
+    fn cells_off_a_bounded_board_are_missing() {
+        let mut board = BitBoard::new(3, 3);
+
+        board.set(Coordinates { x: 3, y: 0 }, CellState::Alive);
+
+        assert_eq!(board.at(Coordinates { x: 3, y: 0 }), None);
+        assert_eq!(board.population(), 0);
+    }
+
+    #[test]
+    fn a_toroidal_board_wraps_around_its_edges() {
+        let mut board = BitBoard::new(4, 3).with_topology(Topology::Toroidal);
+
+        board.set(Coordinates { x: -1, y: 3 }, CellState::Alive);
+
+        assert_eq!(
+            board.at(Coordinates { x: 3, y: 0 }).map(|c| c.cell_state),
+            Some(CellState::Alive)
+        );
+    }
+
+    #[test]
+    fn live_cells_are_read_from_the_words() {
+        let grid = GridBoard::seeded_random(100, 20, 9, 0.3);
+        let board = BitBoard::from(grid.rows.clone());
+
+        assert_eq!(
+            board.live_cells().collect::<Vec<_>>(),
+            grid.live_cells().collect::<Vec<_>>()
+        );
+        assert_eq!(board.population(), grid.population());
+        assert_eq!(board.bounding_box(), grid.bounding_box());
+    }
+
+    #[test]
+    fn statistics_are_read_from_the_words() {
+        use statistics::GenerationStatistics;
+
+        let grid = GridBoard::seeded_random(100, 20, 9, 0.3);
+        let mut board = BitBoard::from(grid.rows.clone());
+        board.set(Coordinates { x: 70, y: 3 }, CellState::Born);
+        board.set(Coordinates { x: 71, y: 3 }, CellState::Died);
+
+        assert_eq!(board.size(), Some((100, 20)));
+        assert_eq!(
+            board.changed_cells().collect::<Vec<_>>(),
+            vec![
+                Cell {
+                    cell_state: CellState::Born,
+                    location: Coordinates { x: 70, y: 3 },
+                },
+                Cell {
+                    cell_state: CellState::Died,
+                    location: Coordinates { x: 71, y: 3 },
+                },
+            ]
+        );
+        let statistics = GenerationStatistics::of(0, &board);
+        assert_eq!((statistics.births, statistics.deaths), (1, 1));
+        assert!(board.rows.get().is_none());
+    }
+
+    #[test]
+    fn finding_a_cell_under_the_mouse_leaves_the_rows_alone() {
+        use interface::Renderer;
+        use renderer::StringRenderer;
+        use std::io::stdout;
+
+        let board = BitBoard::seeded_random(10, 10, 4, 0.5);
+        let renderer = StringRenderer::new(stdout(), 20, 20);
+
+        assert_eq!(
+            renderer.coordinates_at(&board, 3, 4),
+            Some(Coordinates { x: 2, y: 3 })
+        );
+        assert_eq!(renderer.coordinates_at(&board, 11, 4), None);
+        assert!(board.bounding_box().is_some());
+        assert!(board.rows.get().is_none());
+    }
+
+    #[test]
+    fn padding_past_the_end_of_a_row_is_ignored() {
+        let board = BitBoard::with_words(3, 1, vec![!0], vec![!0]);
+
+        assert_eq!(board.population(), 3);
+        assert_eq!(board.alive_words(0), &[0b111]);
+    }
+
+    #[test]
+    fn clearing_kills_every_cell() {
+        let mut board = BitBoard::seeded_random(10, 10, 1, 0.5);
+
+        board.clear();
+
+        assert_eq!(board, BitBoard::new(10, 10));
+        assert!(board.rows().iter().flatten().all(|c| c.cell_state == CellState::Dead));
+    }
+
+    #[test]
+    fn generators_keep_the_topology() {
+        use generator::SimpleGenerator;
+        use interface::Generator;
+
+        let board = BitBoard::fill_with(5, 5, Box::new(|c| if c.y == 2 && (c.x == 4 || c.x <= 1) {
+            CellState::Alive
+        } else {
+            CellState::Dead
+        })).with_topology(Topology::Toroidal);
+
+        let next = SimpleGenerator::new().generate(&board);
+
+        assert_eq!(next.topology(), Topology::Toroidal);
+        assert_eq!(next.population(), 3);
+        assert_eq!(
+            next.at(Coordinates { x: 0, y: 1 }).map(|c| c.cell_state),
+            Some(CellState::Born)
+        );
+    }
+
+    #[test]
+    fn a_large_board_is_small() {
+        let mut board = BitBoard::new(10_000, 10_000);
+
+        board.set(Coordinates { x: 9_999, y: 9_999 }, CellState::Alive);
+
+        assert_eq!(board.alive.len() + board.changed.len(), 2 * 157 * 10_000);
+        assert_eq!(board.population(), 1);
+    }
+}
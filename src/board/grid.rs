@@ -1,5 +1,7 @@
 use interface::{Board, Rows};
 use models::*;
+use board::symmetry::Symmetry;
+use board::{sow, Topology};
 
 extern crate rand;
 use rand::*;
@@ -8,6 +10,7 @@ use rand::*;
 pub struct GridBoard {
     height: i32,
     width: i32,
+    topology: Topology,
     pub rows: Rows,
 }
 
@@ -16,6 +19,7 @@ impl GridBoard {
         GridBoard {
             width: size,
             height: size,
+            topology: Topology::Bounded,
             rows: Vec::new(),
         }
     }
@@ -24,10 +28,27 @@ impl GridBoard {
         GridBoard {
             width: rows[0].len() as i32,
             height: rows.len() as i32,
+            topology: Topology::Bounded,
             rows,
         }
     }
 
+    pub fn with_topology(self, topology: Topology) -> GridBoard {
+        GridBoard { topology, ..self }
+    }
+
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+
+    pub fn height(&self) -> i32 {
+        self.height
+    }
+
     pub fn all_alive(size: i32) -> GridBoard {
         GridBoard::fill_with(size, size, Box::new(|_| CellState::Alive))
     }
@@ -49,6 +70,25 @@ impl GridBoard {
         )
     }
 
+    /// A reproducible random board, where each cell is alive with probability
+    /// `density`.
+    pub fn seeded_random(width: i32, height: i32, seed: u64, density: f64) -> GridBoard {
+        GridBoard::symmetric_random(width, height, seed, density, Symmetry::C1)
+    }
+
+    /// A reproducible random soup with the given symmetry. See
+    /// `symmetry::soup`.
+    pub fn symmetric_random(
+        width: i32,
+        height: i32,
+        seed: u64,
+        density: f64,
+        symmetry: Symmetry,
+    ) -> GridBoard {
+        let empty = GridBoard::fill_with(width, height, Box::new(|_| CellState::Dead));
+        sow(empty, width, height, seed, density, symmetry)
+    }
+
     pub fn diagonal(width: i32, height: i32) -> GridBoard {
         GridBoard::fill_with(
             width,
@@ -61,10 +101,10 @@ impl GridBoard {
         )
     }
 
-    fn fill_with(
+    pub fn fill_with(
         width: i32,
         height: i32,
-        mut state_for_coords: Box<FnMut(Coordinates) -> CellState>,
+        mut state_for_coords: Box<dyn FnMut(Coordinates) -> CellState>,
     ) -> GridBoard {
 
         let mut rows = Vec::with_capacity(height as usize);
@@ -75,7 +115,7 @@ impl GridBoard {
                 let state = state_for_coords(location);
                 let cell = Cell {
                     cell_state: state,
-                    location: location,
+                    location,
                 };
                 row.push(cell);
             }
@@ -85,26 +125,67 @@ impl GridBoard {
         GridBoard {
             width,
             height,
+            topology: Topology::Bounded,
             rows,
         }
     }
 }
 
+impl GridBoard {
+    /// Finds where the cell at `coordinates` is stored, wrapping around the
+    /// edges of a toroidal board.
+    fn index_of(&self, coordinates: Coordinates) -> Option<(usize, usize)> {
+        let coordinates = match self.topology {
+            Topology::Toroidal if self.width > 0 && self.height > 0 => Coordinates {
+                x: coordinates.x.rem_euclid(self.width),
+                y: coordinates.y.rem_euclid(self.height),
+            },
+            _ => coordinates,
+        };
+
+        if coordinates.x >= 0
+            && coordinates.y >= 0
+            && coordinates.y < self.height
+            && coordinates.x < self.width
+        {
+            return Some((coordinates.x as usize, coordinates.y as usize));
+        }
+        None
+    }
+}
+
 impl Board for GridBoard {
     fn at(&self, coordinates: Coordinates) -> Option<Cell> {
-        if coordinates.x >= 0 && coordinates.y >= 0 {
-            if coordinates.y < self.height {
-                if coordinates.x < self.width {
-                    return Some(self.rows[coordinates.y as usize][coordinates.x as usize]);
-                }
-            }
+        self.index_of(coordinates)
+            .map(|(x, y)| self.rows[y][x])
+    }
+
+    fn set(&mut self, coordinates: Coordinates, cell_state: CellState) {
+        if let Some((x, y)) = self.index_of(coordinates) {
+            self.rows[y][x].cell_state = cell_state;
+        }
+    }
+
+    fn clear(&mut self) {
+        for cell in self.rows.iter_mut().flat_map(|row| row.iter_mut()) {
+            cell.cell_state = CellState::Dead;
         }
-        None
     }
 
     fn rows(&self) -> &Vec<Vec<Cell>> {
         &self.rows
     }
+
+    fn torus_size(&self) -> Option<(i32, i32)> {
+        match self.topology {
+            Topology::Toroidal => Some((self.width, self.height)),
+            Topology::Bounded => None,
+        }
+    }
+
+    fn successor(&self, rows: Rows) -> GridBoard {
+        GridBoard::with_rows(rows).with_topology(self.topology)
+    }
 }
 
 impl From<Rows> for GridBoard {
@@ -150,4 +231,152 @@ mod tests {
         let none_cell = board.at(outside_of_board);
         assert_eq!(none_cell, None);
     }
+
+    #[test]
+    fn seeded_random_boards_are_reproducible() {
+        let board = GridBoard::seeded_random(20, 10, 42, 0.5);
+
+        assert_eq!(board, GridBoard::seeded_random(20, 10, 42, 0.5));
+        assert_ne!(board, GridBoard::seeded_random(20, 10, 43, 0.5));
+    }
+
+    #[test]
+    fn density_is_the_chance_of_a_cell_being_alive() {
+        let empty = GridBoard::seeded_random(5, 5, 1, 0.0);
+        let full = GridBoard::seeded_random(5, 5, 1, 1.0);
+
+        assert_eq!(empty, GridBoard::fill_with(5, 5, Box::new(|_| CellState::Dead)));
+        assert_eq!(full, GridBoard::all_alive(5));
+    }
+
+    #[test]
+    fn symmetric_soups_are_reproducible() {
+        let board = GridBoard::symmetric_random(9, 9, 42, 0.5, Symmetry::D8);
+
+        assert_eq!(board, GridBoard::symmetric_random(9, 9, 42, 0.5, Symmetry::D8));
+        assert_eq!(
+            GridBoard::symmetric_random(20, 10, 42, 0.5, Symmetry::C1),
+            GridBoard::seeded_random(20, 10, 42, 0.5)
+        );
+    }
+
+    #[test]
+    fn a_c2_soup_looks_the_same_upside_down() {
+        let board = GridBoard::symmetric_random(9, 6, 7, 0.5, Symmetry::C2);
+
+        for cell in board.rows.iter().flat_map(|row| row.iter()) {
+            let opposite = Coordinates {
+                x: 8 - cell.location.x,
+                y: 5 - cell.location.y,
+            };
+            assert_eq!(board.at(opposite).unwrap().cell_state, cell.cell_state);
+        }
+    }
+
+    #[test]
+    fn a_toroidal_board_wraps_around_its_edges() {
+        let board = GridBoard::diagonal(4, 3).with_topology(Topology::Toroidal);
+
+        assert_eq!(
+            board.at(Coordinates { x: -1, y: -1 }),
+            board.at(Coordinates { x: 3, y: 2 })
+        );
+        assert_eq!(
+            board.at(Coordinates { x: 4, y: 5 }),
+            board.at(Coordinates { x: 0, y: 2 })
+        );
+    }
+
+    #[test]
+    fn cells_can_be_set() {
+        let mut board = GridBoard::all_alive(3);
+
+        board.set(Coordinates { x: 1, y: 2 }, CellState::Dead);
+        board.set(Coordinates { x: 5, y: 5 }, CellState::Dead);
+
+        assert_eq!(
+            board.at(Coordinates { x: 1, y: 2 }).map(|c| c.cell_state),
+            Some(CellState::Dead)
+        );
+        assert_eq!(board, {
+            let mut expected = GridBoard::all_alive(3);
+            expected.rows[2][1].cell_state = CellState::Dead;
+            expected
+        });
+    }
+
+    #[test]
+    fn setting_cells_wraps_around_a_torus() {
+        let mut board = GridBoard::all_alive(3).with_topology(Topology::Toroidal);
+
+        board.set(Coordinates { x: -1, y: 3 }, CellState::Dead);
+
+        assert_eq!(board.rows[0][2].cell_state, CellState::Dead);
+    }
+
+    #[test]
+    fn live_cells_include_those_just_born() {
+        let mut board = GridBoard::fill_with(4, 3, Box::new(|_| CellState::Dead));
+        board.set(Coordinates { x: 1, y: 0 }, CellState::Alive);
+        board.set(Coordinates { x: 3, y: 2 }, CellState::Born);
+        board.set(Coordinates { x: 0, y: 2 }, CellState::Died);
+
+        assert_eq!(
+            board.live_cells().collect::<Vec<_>>(),
+            vec![Coordinates { x: 1, y: 0 }, Coordinates { x: 3, y: 2 }]
+        );
+        assert_eq!(board.population(), 2);
+        assert_eq!(
+            board.bounding_box(),
+            Some(BoundingBox {
+                min: Coordinates { x: 1, y: 0 },
+                max: Coordinates { x: 3, y: 2 },
+            })
+        );
+    }
+
+    #[test]
+    fn clearing_kills_every_cell() {
+        let mut board = GridBoard::all_alive(3);
+
+        board.clear();
+
+        assert_eq!(board.population(), 0);
+        assert_eq!(board.bounding_box(), None);
+        assert_eq!(board, GridBoard::fill_with(3, 3, Box::new(|_| CellState::Dead)));
+    }
+
+    #[test]
+    fn successors_keep_the_topology() {
+        let board = GridBoard::all_alive(3).with_topology(Topology::Toroidal);
+
+        let next = board.successor(board.rows.clone());
+
+        assert_eq!(next.topology(), Topology::Toroidal);
+    }
+
+    #[test]
+    fn a_blinker_across_the_edge_of_a_torus_keeps_blinking() {
+        use generator::SimpleGenerator;
+        use interface::Generator;
+
+        let board = GridBoard::fill_with(
+            5,
+            5,
+            Box::new(|c| if c.y == 2 && (c.x == 4 || c.x == 0 || c.x == 1) {
+                CellState::Alive
+            } else {
+                CellState::Dead
+            }),
+        ).with_topology(Topology::Toroidal);
+        let generator = SimpleGenerator::new();
+
+        let next = generator.generate(&generator.generate(&board));
+
+        for cell in next.rows.iter().flat_map(|row| row.iter()) {
+            let expected = board.at(cell.location).unwrap().cell_state;
+            let alive = cell.cell_state == CellState::Alive || cell.cell_state == CellState::Born;
+            assert_eq!(alive, expected == CellState::Alive);
+        }
+    }
 }
@@ -1 +1,34 @@
+use interface::{Board, CellState};
+
+use self::symmetry::Symmetry;
+
+pub mod bit;
 pub mod grid;
+pub mod sparse;
+pub mod symmetry;
+
+/// How a bounded board treats coordinates beyond its edges.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Default)]
+pub enum Topology {
+    /// Cells outside the board don't exist.
+    #[default]
+    Bounded,
+    /// The edges wrap around, so the board behaves like the surface of a torus.
+    Toroidal,
+}
+
+/// Brings a reproducible random soup to life on `board`, which should be an
+/// empty `width` x `height` board. See `symmetry::soup`.
+fn sow<T: Board>(
+    mut board: T,
+    width: i32,
+    height: i32,
+    seed: u64,
+    density: f64,
+    symmetry: Symmetry,
+) -> T {
+    for location in symmetry::soup_cells(width, height, seed, density, symmetry) {
+        board.set(location, CellState::Alive);
+    }
+    board
+}
@@ -0,0 +1,291 @@
+use std::collections::HashMap;
+
+use interface::{Board, Rows};
+use models::*;
+use board::grid::GridBoard;
+
+/// An unbounded board that only stores the cells that are alive, along with
+/// those that have just died so they can still be rendered.
+///
+/// `rows` covers the bounding box of the living cells plus a one cell margin,
+/// which is every cell that could change in the next generation.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SparseBoard {
+    cells: HashMap<Coordinates, CellState>,
+    rows: Rows,
+}
+
+impl SparseBoard {
+    pub fn new() -> SparseBoard {
+        SparseBoard::with_cells(HashMap::new())
+    }
+
+    pub fn with_live_cells<I>(live_cells: I) -> SparseBoard
+    where
+        I: IntoIterator<Item = Coordinates>,
+    {
+        SparseBoard::with_cells(
+            live_cells
+                .into_iter()
+                .map(|location| (location, CellState::Alive))
+                .collect(),
+        )
+    }
+
+    /// Builds the board that follows this one from the cells alive in it,
+    /// marking the cells that were born or died along the way.
+    pub fn succeeded_by<I>(&self, live_cells: I) -> SparseBoard
+    where
+        I: IntoIterator<Item = Coordinates>,
+    {
+        let mut cells: HashMap<Coordinates, CellState> = live_cells
+            .into_iter()
+            .map(|location| {
+                let state = if self.state_at(location).is_alive() {
+                    CellState::Alive
+                } else {
+                    CellState::Born
+                };
+                (location, state)
+            })
+            .collect();
+        for (&location, &state) in &self.cells {
+            if state.is_alive() && !cells.contains_key(&location) {
+                cells.insert(location, CellState::Died);
+            }
+        }
+
+        SparseBoard::with_cells(cells)
+    }
+
+    fn with_cells(cells: HashMap<Coordinates, CellState>) -> SparseBoard {
+        let rows = window(&cells);
+        SparseBoard { cells, rows }
+    }
+
+    /// Copies the `width` x `height` region whose top left corner is at
+    /// `origin` into a `GridBoard`, with locations relative to `origin`.
+    pub fn viewport(&self, origin: Coordinates, width: i32, height: i32) -> GridBoard {
+        assert!(
+            width > 0 && height > 0,
+            "a viewport must be at least one cell wide and tall"
+        );
+        let rows = (0..height)
+            .map(|y| {
+                (0..width)
+                    .map(|x| Cell {
+                        cell_state: self.state_at(Coordinates {
+                            x: origin.x + x,
+                            y: origin.y + y,
+                        }),
+                        location: Coordinates { x, y },
+                    })
+                    .collect()
+            })
+            .collect();
+
+        GridBoard::with_rows(rows)
+    }
+
+    fn state_at(&self, location: Coordinates) -> CellState {
+        self.cells
+            .get(&location)
+            .cloned()
+            .unwrap_or(CellState::Dead)
+    }
+}
+
+impl Default for SparseBoard {
+    fn default() -> SparseBoard {
+        SparseBoard::new()
+    }
+}
+
+fn window(cells: &HashMap<Coordinates, CellState>) -> Rows {
+    let bounds = match BoundingBox::around(
+        cells
+            .iter()
+            .filter(|&(_, state)| state.is_alive())
+            .map(|(&location, _)| location),
+    ) {
+        Some(bounds) => bounds,
+        None => return Vec::new(),
+    };
+
+    // The margin stops at the edges of the coordinates.
+    (bounds.min.y.saturating_sub(1)..=bounds.max.y.saturating_add(1))
+        .map(|y| {
+            (bounds.min.x.saturating_sub(1)..=bounds.max.x.saturating_add(1))
+                .map(|x| {
+                    let location = Coordinates { x, y };
+                    Cell {
+                        cell_state: cells.get(&location).cloned().unwrap_or(CellState::Dead),
+                        location,
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+impl Board for SparseBoard {
+    fn at(&self, coordinates: Coordinates) -> Option<Cell> {
+        Some(Cell {
+            cell_state: self.state_at(coordinates),
+            location: coordinates,
+        })
+    }
+
+    fn rows(&self) -> &Rows {
+        &self.rows
+    }
+
+    fn set(&mut self, coordinates: Coordinates, cell_state: CellState) {
+        if cell_state == CellState::Dead {
+            self.cells.remove(&coordinates);
+        } else {
+            self.cells.insert(coordinates, cell_state);
+        }
+        self.rows = window(&self.cells);
+    }
+
+    fn live_cells<'a>(&'a self) -> Box<dyn Iterator<Item = Coordinates> + 'a> {
+        Box::new(
+            self.cells
+                .iter()
+                .filter(|&(_, state)| state.is_alive())
+                .map(|(&location, _)| location),
+        )
+    }
+
+    fn changed_cells<'a>(&'a self) -> Box<dyn Iterator<Item = Cell> + 'a> {
+        Box::new(
+            self.cells
+                .iter()
+                .filter(|&(_, &state)| state == CellState::Born || state == CellState::Died)
+                .map(|(&location, &cell_state)| Cell {
+                    cell_state,
+                    location,
+                }),
+        )
+    }
+
+    fn size(&self) -> Option<(i32, i32)> {
+        None
+    }
+
+    fn clear(&mut self) {
+        *self = SparseBoard::new();
+    }
+}
+
+impl From<Rows> for SparseBoard {
+    fn from(rows: Rows) -> SparseBoard {
+        SparseBoard::with_cells(
+            rows.into_iter()
+                .flat_map(|row| row.into_iter())
+                .filter(|cell| cell.cell_state != CellState::Dead)
+                .map(|cell| (cell.location, cell.cell_state))
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use generator::SimpleGenerator;
+    use interface::Generator;
+
+    fn glider() -> SparseBoard {
+        SparseBoard::with_live_cells(vec![
+            Coordinates { x: 1, y: 0 },
+            Coordinates { x: 2, y: 1 },
+            Coordinates { x: 0, y: 2 },
+            Coordinates { x: 1, y: 2 },
+            Coordinates { x: 2, y: 2 },
+        ])
+    }
+
+    fn live_cells(board: &SparseBoard) -> Vec<Coordinates> {
+        let mut cells: Vec<Coordinates> = board.live_cells().collect();
+        cells.sort_by_key(|c| (c.y, c.x));
+        cells
+    }
+
+    #[test]
+    fn cells_anywhere_on_the_plane_exist() {
+        let board = SparseBoard::new();
+        let far_away = Coordinates {
+            x: -1_000_000,
+            y: 1_000_000,
+        };
+
+        assert_eq!(
+            board.at(far_away),
+            Some(Cell {
+                cell_state: CellState::Dead,
+                location: far_away,
+            })
+        );
+    }
+
+    #[test]
+    fn rows_cover_the_living_cells_and_their_neighbours() {
+        let board = glider();
+
+        assert_eq!(board.rows().len(), 5);
+        assert_eq!(board.rows()[0][0].location, Coordinates { x: -1, y: -1 });
+    }
+
+    #[test]
+    fn a_glider_travels_without_limit() {
+        let generator = SimpleGenerator::new();
+        let mut board = glider();
+        for _ in 0..40 {
+            board = generator.generate(&board);
+        }
+
+        let expected: Vec<Coordinates> = live_cells(&glider())
+            .into_iter()
+            .map(|c| Coordinates {
+                x: c.x + 10,
+                y: c.y + 10,
+            })
+            .collect();
+        assert_eq!(live_cells(&board), expected);
+    }
+
+    #[test]
+    fn setting_cells_grows_the_rows() {
+        let mut board = glider();
+
+        board.set(Coordinates { x: -5, y: 0 }, CellState::Alive);
+        board.set(Coordinates { x: 1, y: 0 }, CellState::Dead);
+
+        assert_eq!(board.rows()[0][0].location, Coordinates { x: -6, y: -1 });
+        assert_eq!(board.population(), 5);
+    }
+
+    #[test]
+    fn the_viewport_is_relative_to_its_origin() {
+        let board = glider();
+
+        let view = board.viewport(Coordinates { x: 1, y: 1 }, 2, 2);
+
+        assert_eq!(
+            view.at(Coordinates { x: 1, y: 0 }).map(|c| c.cell_state),
+            Some(CellState::Alive)
+        );
+        assert_eq!(
+            view.at(Coordinates { x: 0, y: 0 }).map(|c| c.cell_state),
+            Some(CellState::Dead)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one cell wide and tall")]
+    fn an_empty_viewport_is_rejected() {
+        glider().viewport(Coordinates { x: 0, y: 0 }, 3, 0);
+    }
+}
@@ -0,0 +1,262 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use models::*;
+
+extern crate rand;
+use rand::*;
+
+/// The symmetry of a random soup, as used in soup searches. Symmetric soups
+/// are far more likely to produce symmetric objects than plain ones.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Default)]
+pub enum Symmetry {
+    /// No symmetry at all.
+    #[default]
+    C1,
+    /// Unchanged by a half turn.
+    C2,
+    /// Unchanged by a quarter turn.
+    C4,
+    /// Mirrored left to right and top to bottom.
+    D4,
+    /// Unchanged by any rotation or reflection of the square.
+    D8,
+}
+
+impl Symmetry {
+    /// Whether the symmetry only makes sense on a square.
+    pub fn needs_a_square(self) -> bool {
+        self == Symmetry::C4 || self == Symmetry::D8
+    }
+
+    /// Every cell that `coordinates` is mapped onto by the symmetry, itself
+    /// included, in a `width` x `height` region with its top left corner at
+    /// the origin.
+    pub fn images(self, coordinates: Coordinates, width: i32, height: i32) -> Vec<Coordinates> {
+        let Coordinates { x, y } = coordinates;
+        let (right, bottom) = (width - 1 - x, height - 1 - y);
+        let points = match self {
+            Symmetry::C1 => vec![(x, y)],
+            Symmetry::C2 => vec![(x, y), (right, bottom)],
+            Symmetry::D4 => vec![(x, y), (right, y), (x, bottom), (right, bottom)],
+            Symmetry::C4 => vec![
+                (x, y),
+                (width - 1 - y, x),
+                (right, bottom),
+                (y, height - 1 - x),
+            ],
+            Symmetry::D8 => vec![
+                (x, y),
+                (right, y),
+                (x, bottom),
+                (right, bottom),
+                (y, x),
+                (width - 1 - y, x),
+                (y, height - 1 - x),
+                (width - 1 - y, height - 1 - x),
+            ],
+        };
+        points
+            .into_iter()
+            .map(|(x, y)| Coordinates { x, y })
+            .collect()
+    }
+}
+
+impl fmt::Display for Symmetry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl FromStr for Symmetry {
+    type Err = ParseSymmetryError;
+
+    fn from_str(s: &str) -> Result<Symmetry, ParseSymmetryError> {
+        match s.to_uppercase().as_str() {
+            "C1" => Ok(Symmetry::C1),
+            "C2" => Ok(Symmetry::C2),
+            "C4" => Ok(Symmetry::C4),
+            "D4" => Ok(Symmetry::D4),
+            "D8" => Ok(Symmetry::D8),
+            _ => Err(ParseSymmetryError {
+                symmetry: s.to_string(),
+            }),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ParseSymmetryError {
+    symmetry: String,
+}
+
+impl fmt::Display for ParseSymmetryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "unknown symmetry '{}' (expected C1, C2, C4, D4 or D8)",
+            self.symmetry
+        )
+    }
+}
+
+impl Error for ParseSymmetryError {}
+
+/// The states of a reproducible random soup, row by row, where each set of
+/// cells the symmetry maps onto each other is alive with probability
+/// `density`.
+///
+/// A soup with C4 or D8 symmetry fills the largest square centred on the
+/// board, leaving the rest dead. With C1 symmetry the soup matches
+/// `GridBoard::seeded_random` with the same seed.
+pub fn soup(
+    width: i32,
+    height: i32,
+    seed: u64,
+    density: f64,
+    symmetry: Symmetry,
+) -> Vec<CellState> {
+    let (width, height) = (width.max(0), height.max(0));
+    let (soup_width, soup_height) = if symmetry.needs_a_square() {
+        let side = width.min(height);
+        (side, side)
+    } else {
+        (width, height)
+    };
+    let left = (width - soup_width) / 2;
+    let top = (height - soup_height) / 2;
+    let index = |c: Coordinates| (c.y + top) as usize * width as usize + (c.x + left) as usize;
+
+    let mut rng = Isaac64Rng::from_seed(&[seed]);
+    let mut states = vec![CellState::Dead; width as usize * height as usize];
+    for y in 0..soup_height {
+        for x in 0..soup_width {
+            let location = Coordinates { x, y };
+            let first = symmetry
+                .images(location, soup_width, soup_height)
+                .into_iter()
+                .min_by_key(|c| (c.y, c.x))
+                .unwrap_or(location);
+            states[index(location)] = if first != location {
+                states[index(first)]
+            } else if rng.gen::<f64>() < density {
+                CellState::Alive
+            } else {
+                CellState::Dead
+            };
+        }
+    }
+    states
+}
+
+/// The living cells of a reproducible random soup. See `soup`.
+pub fn soup_cells(
+    width: i32,
+    height: i32,
+    seed: u64,
+    density: f64,
+    symmetry: Symmetry,
+) -> Vec<Coordinates> {
+    let states = soup(width, height, seed, density, symmetry);
+    (0..height)
+        .flat_map(|y| (0..width).map(move |x| Coordinates { x, y }))
+        .zip(states)
+        .filter(|&(_, state)| state.is_alive())
+        .map(|(location, _)| location)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_symmetric(states: &[CellState], size: i32, symmetry: Symmetry) -> bool {
+        let at = |x: i32, y: i32| y as usize * size as usize + x as usize;
+        (0..size).all(|y| {
+            (0..size).all(|x| {
+                symmetry
+                    .images(Coordinates { x, y }, size, size)
+                    .iter()
+                    .all(|c| states[at(c.x, c.y)] == states[at(x, y)])
+            })
+        })
+    }
+
+    #[test]
+    fn parses_symmetries() {
+        assert_eq!("c2".parse(), Ok(Symmetry::C2));
+        assert_eq!("D8".parse(), Ok(Symmetry::D8));
+        assert!("C3".parse::<Symmetry>().is_err());
+        assert_eq!(Symmetry::D4.to_string(), "D4");
+    }
+
+    #[test]
+    fn images_of_a_cell() {
+        let corner = Coordinates { x: 0, y: 1 };
+
+        assert_eq!(Symmetry::C1.images(corner, 4, 3), vec![corner]);
+        assert_eq!(
+            Symmetry::C2.images(corner, 4, 3),
+            vec![corner, Coordinates { x: 3, y: 1 }]
+        );
+        assert_eq!(
+            Symmetry::C4.images(corner, 4, 4),
+            vec![
+                corner,
+                Coordinates { x: 2, y: 0 },
+                Coordinates { x: 3, y: 2 },
+                Coordinates { x: 1, y: 3 },
+            ]
+        );
+    }
+
+    #[test]
+    fn soups_have_their_symmetry() {
+        for &symmetry in &[
+            Symmetry::C1,
+            Symmetry::C2,
+            Symmetry::C4,
+            Symmetry::D4,
+            Symmetry::D8,
+        ] {
+            for &size in &[6, 7] {
+                let states = soup(size, size, 11, 0.5, symmetry);
+                assert!(
+                    is_symmetric(&states, size, symmetry),
+                    "{} {}",
+                    symmetry,
+                    size
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn square_symmetries_fill_a_centred_square() {
+        let states = soup(7, 3, 2, 1.0, Symmetry::D8);
+
+        for (i, state) in states.iter().enumerate() {
+            let x = i % 7;
+            let expected = if (2..5).contains(&x) {
+                CellState::Alive
+            } else {
+                CellState::Dead
+            };
+            assert_eq!(*state, expected, "cell {}", i);
+        }
+    }
+
+    #[test]
+    fn soups_are_reproducible() {
+        assert_eq!(
+            soup(8, 8, 1, 0.5, Symmetry::C4),
+            soup(8, 8, 1, 0.5, Symmetry::C4)
+        );
+        assert_ne!(
+            soup(8, 8, 1, 0.5, Symmetry::C4),
+            soup(8, 8, 2, 0.5, Symmetry::C4)
+        );
+    }
+}
@@ -0,0 +1,471 @@
+//! Counting the objects left behind once a soup has settled down.
+//!
+//! The living cells are split into clusters, each cluster is run on its own
+//! to find out whether it's a still life, an oscillator or a spaceship, and
+//! its shape is put in a canonical form so that the same object is counted
+//! together whatever its orientation or phase.
+
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+
+use board::sparse::SparseBoard;
+use cycle::{Classification, CycleDetector};
+use generator::SimpleGenerator;
+use interface::{Board, Generator};
+use models::Coordinates;
+use pattern::apgcode;
+use pattern::canonical::{cells_in, orient};
+use rule::Rule;
+
+pub use pattern::canonical::Kind;
+
+const DEFAULT_MAX_GENERATIONS: u32 = 1000;
+
+/// Named objects, drawn in plaintext with `O` for living cells. They're only
+/// recognised under Conway's rule.
+const KNOWN_OBJECTS: &[(&str, &str)] = &[
+    ("block", "OO/OO"),
+    ("beehive", ".OO./O..O/.OO."),
+    ("loaf", ".OO./O..O/.O.O/..O."),
+    ("boat", "OO./O.O/.O."),
+    ("ship", "OO./O.O/.OO"),
+    ("tub", ".O./O.O/.O."),
+    ("pond", ".OO./O..O/O..O/.OO."),
+    ("long boat", "OO../O.O./.O.O/..O."),
+    ("barge", ".O../O.O./.O.O/..O."),
+    ("mango", ".OO../O..O./.O..O/..OO."),
+    ("blinker", "OOO"),
+    ("toad", ".OOO/OOO."),
+    ("beacon", "OO../OO../..OO/..OO"),
+    ("glider", ".O./..O/OOO"),
+    ("lightweight spaceship", ".O..O/O..../O...O/OOOO."),
+];
+
+/// Splits cells into clusters, where two cells are in the same cluster if
+/// they're no more than `radius` cells apart horizontally and vertically. A
+/// radius of one joins cells that are neighbours.
+///
+/// On a torus of `torus_size`, clusters join up across the edges. Each
+/// cluster's cells are given as if the board didn't wrap, so they may lie
+/// outside it.
+pub fn components(
+    cells: &[Coordinates],
+    radius: i32,
+    torus_size: Option<(i32, i32)>,
+) -> Vec<Vec<Coordinates>> {
+    let wrap = |c: Coordinates| match torus_size {
+        Some((width, height)) => Coordinates {
+            x: c.x.rem_euclid(width),
+            y: c.y.rem_euclid(height),
+        },
+        None => c,
+    };
+    let mut remaining: HashSet<Coordinates> = cells.iter().map(|&c| wrap(c)).collect();
+    let mut components = Vec::new();
+
+    for &start in cells {
+        if !remaining.remove(&wrap(start)) {
+            continue;
+        }
+        let mut component = vec![start];
+        let mut queue = VecDeque::new();
+        queue.push_back(start);
+        while let Some(cell) = queue.pop_front() {
+            for dy in -radius..radius + 1 {
+                for dx in -radius..radius + 1 {
+                    let next = Coordinates {
+                        x: cell.x + dx,
+                        y: cell.y + dy,
+                    };
+                    if remaining.remove(&wrap(next)) {
+                        component.push(next);
+                        queue.push_back(next);
+                    }
+                }
+            }
+        }
+        components.push(component);
+    }
+    components
+}
+
+/// A set of cells, moved so that its top left corner is at the origin and
+/// turned to whichever of its eight rotations and reflections comes first,
+/// so that the same shape in any orientation is equal.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct Shape {
+    cells: Vec<Coordinates>,
+}
+
+impl Shape {
+    pub fn of(cells: &[Coordinates]) -> Shape {
+        (0..8)
+            .map(|orientation| {
+                Shape::translated(cells.iter().map(|&c| orient(c, orientation)).collect())
+            })
+            .min()
+            .unwrap_or(Shape { cells: Vec::new() })
+    }
+
+    /// Moves the cells so that the top left corner is at the origin, and
+    /// sorts them row by row.
+    fn translated(mut cells: Vec<Coordinates>) -> Shape {
+        let min_x = cells.iter().map(|c| c.x).min().unwrap_or(0);
+        let min_y = cells.iter().map(|c| c.y).min().unwrap_or(0);
+        for cell in &mut cells {
+            cell.x -= min_x;
+            cell.y -= min_y;
+        }
+        cells.sort_by_key(|c| (c.y, c.x));
+        cells.dedup();
+        Shape { cells }
+    }
+
+    /// The cells, row by row.
+    pub fn cells(&self) -> &[Coordinates] {
+        &self.cells
+    }
+
+    pub fn population(&self) -> usize {
+        self.cells.len()
+    }
+}
+
+impl Ord for Shape {
+    fn cmp(&self, other: &Shape) -> Ordering {
+        let key =
+            |shape: &Shape| -> Vec<(i32, i32)> { shape.cells.iter().map(|c| (c.y, c.x)).collect() };
+        key(self).cmp(&key(other))
+    }
+}
+
+impl PartialOrd for Shape {
+    fn partial_cmp(&self, other: &Shape) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// An object found by a census. Oscillators and spaceships are described by
+/// whichever of their phases has the first shape.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct Object {
+    pub kind: Kind,
+    pub shape: Shape,
+    pub name: Option<&'static str>,
+    /// The canonical apgcode, which unstable objects don't have.
+    pub apgcode: Option<String>,
+}
+
+impl fmt::Display for Object {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(name) = self.name {
+            return write!(f, "{}", name);
+        }
+        match self.apgcode {
+            Some(ref apgcode) => write!(f, "{}", apgcode),
+            None => write!(
+                f,
+                "unstable object with {} cells",
+                self.shape.population()
+            ),
+        }
+    }
+}
+
+/// How many of each object a board holds.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Census {
+    counts: HashMap<Object, usize>,
+}
+
+impl Census {
+    /// Each object with its count, most common first.
+    pub fn counts(&self) -> Vec<(&Object, usize)> {
+        let mut counts: Vec<(&Object, usize)> = self.counts.iter().map(|(o, &n)| (o, n)).collect();
+        counts.sort_by(|a, b| {
+            b.1.cmp(&a.1)
+                .then_with(|| a.0.to_string().cmp(&b.0.to_string()))
+        });
+        counts
+    }
+
+    /// The number of objects with the given name.
+    pub fn count_of(&self, name: &str) -> usize {
+        self.counts
+            .iter()
+            .filter(|&(o, _)| o.name == Some(name))
+            .map(|(_, &n)| n)
+            .sum()
+    }
+
+    /// The number of objects of every kind.
+    pub fn total(&self) -> usize {
+        self.counts.values().sum()
+    }
+}
+
+impl fmt::Display for Census {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (object, count) in self.counts() {
+            writeln!(f, "{:>6}  {}", count, object)?;
+        }
+        Ok(())
+    }
+}
+
+/// Takes censuses of boards.
+pub struct CensusTaker {
+    rule: Rule,
+    radius: i32,
+    max_generations: u32,
+    known: HashMap<(Kind, Shape), &'static str>,
+}
+
+impl Default for CensusTaker {
+    fn default() -> CensusTaker {
+        CensusTaker::new()
+    }
+}
+
+impl CensusTaker {
+    pub fn new() -> CensusTaker {
+        CensusTaker::with_rule(Rule::conway())
+    }
+
+    pub fn with_rule(rule: Rule) -> CensusTaker {
+        let mut taker = CensusTaker {
+            rule,
+            radius: 1,
+            max_generations: DEFAULT_MAX_GENERATIONS,
+            known: HashMap::new(),
+        };
+        if rule == Rule::conway() {
+            for &(name, drawing) in KNOWN_OBJECTS {
+                let object = taker.identify(&cells_in(drawing));
+                taker.known.insert((object.kind, object.shape), name);
+            }
+        }
+        taker
+    }
+
+    /// How far apart cells can be and still belong to the same object.
+    /// Objects like the beacon, whose parts only touch in some phases, need
+    /// a radius of two.
+    pub fn with_radius(self, radius: i32) -> CensusTaker {
+        CensusTaker { radius, ..self }
+    }
+
+    /// How long an object is run for to find out what it is.
+    pub fn with_max_generations(self, max_generations: u32) -> CensusTaker {
+        CensusTaker {
+            max_generations,
+            ..self
+        }
+    }
+
+    /// Counts the objects on a board, which should already have settled
+    /// down.
+    pub fn take<T: Board>(&self, board: &T) -> Census {
+        let cells: Vec<Coordinates> = board.live_cells().collect();
+        let mut census = Census::default();
+        for component in components(&cells, self.radius, board.torus_size()) {
+            *census.counts.entry(self.identify(&component)).or_insert(0) += 1;
+        }
+        census
+    }
+
+    /// Runs an object on its own until it repeats, to find out what it is.
+    pub fn identify(&self, cells: &[Coordinates]) -> Object {
+        let generator = SimpleGenerator::with_rule(self.rule);
+        let mut detector = CycleDetector::new();
+        let mut board = SparseBoard::with_live_cells(cells.iter().cloned());
+
+        for _ in 0..self.max_generations {
+            let (kind, period) = match detector.observe(&board) {
+                None => {
+                    board = generator.generate(&board);
+                    continue;
+                }
+                Some(Classification::Extinct) => break,
+                Some(Classification::Static) => (Kind::StillLife, 1),
+                Some(Classification::Oscillating { period }) => {
+                    (Kind::Oscillator { period }, period)
+                }
+                Some(Classification::Spaceship { period, .. }) => {
+                    (Kind::Spaceship { period }, period)
+                }
+            };
+
+            let mut phases = vec![board.live_cells().collect::<Vec<_>>()];
+            for _ in 1..period {
+                board = generator.generate(&board);
+                phases.push(board.live_cells().collect());
+            }
+            return self.named(kind, &phases);
+        }
+
+        self.named(Kind::Unstable, &[cells.to_vec()])
+    }
+
+    /// Identifies the object made up of every living cell on a board, and
+    /// gives its apgcode.
+    pub fn apgcode_of<T: Board>(&self, board: &T) -> Option<String> {
+        self.identify(&board.live_cells().collect::<Vec<_>>()).apgcode
+    }
+
+    fn named(&self, kind: Kind, phases: &[Vec<Coordinates>]) -> Object {
+        let shape = phases
+            .iter()
+            .map(|cells| Shape::of(cells))
+            .min()
+            .unwrap_or(Shape { cells: Vec::new() });
+        let name = self.known.get(&(kind, shape.clone())).cloned();
+        Object {
+            kind,
+            shape,
+            name,
+            apgcode: apgcode::encode(kind, phases),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use board::grid::GridBoard;
+    use board::Topology;
+    use models::CellState;
+
+    fn board_with(width: i32, height: i32, objects: &[(&str, i32, i32)]) -> GridBoard {
+        let mut board = GridBoard::fill_with(width, height, Box::new(|_| CellState::Dead));
+        for &(drawing, left, top) in objects {
+            for cell in cells_in(drawing) {
+                let location = Coordinates {
+                    x: cell.x + left,
+                    y: cell.y + top,
+                };
+                board.set(location, CellState::Alive);
+            }
+        }
+        board
+    }
+
+    #[test]
+    fn cells_that_touch_are_one_component() {
+        let cells = cells_in("OO.O/....");
+
+        let mut sizes: Vec<usize> = components(&cells, 1, None)
+            .iter()
+            .map(|c| c.len())
+            .collect();
+        sizes.sort();
+
+        assert_eq!(sizes, vec![1, 2]);
+        assert_eq!(components(&cells, 2, None).len(), 1);
+    }
+
+    #[test]
+    fn components_join_up_across_the_edges_of_a_torus() {
+        let cells = vec![Coordinates { x: 0, y: 0 }, Coordinates { x: 4, y: 0 }];
+
+        let joined = components(&cells, 1, Some((5, 5)));
+
+        assert_eq!(joined.len(), 1);
+        assert_eq!(
+            Shape::of(&joined[0]),
+            Shape::of(&[Coordinates { x: 0, y: 0 }, Coordinates { x: 1, y: 0 }])
+        );
+    }
+
+    #[test]
+    fn shapes_are_the_same_in_any_orientation() {
+        let glider = cells_in(".O./..O/OOO");
+        let turned = cells_in("OO./O.O/O..");
+
+        assert_eq!(Shape::of(&glider), Shape::of(&turned));
+        assert_ne!(Shape::of(&glider), Shape::of(&cells_in("OOO/O../...")));
+    }
+
+    #[test]
+    fn identifies_objects_by_how_they_behave() {
+        let taker = CensusTaker::new();
+
+        assert_eq!(taker.identify(&cells_in("OO/OO")).name, Some("block"));
+        assert_eq!(taker.identify(&cells_in("O/O/O")).name, Some("blinker"));
+        assert_eq!(
+            taker.identify(&cells_in("OO./O.O/O..")).kind,
+            Kind::Spaceship { period: 4 }
+        );
+        assert_eq!(taker.identify(&cells_in("O")).kind, Kind::Unstable);
+    }
+
+    #[test]
+    fn objects_have_apgcodes() {
+        let taker = CensusTaker::new();
+
+        assert_eq!(
+            taker.identify(&cells_in("OO./O.O/O..")).apgcode,
+            Some("xq4_153".to_string())
+        );
+        assert_eq!(taker.identify(&cells_in("O")).apgcode, None);
+        assert_eq!(
+            taker.apgcode_of(&board_with(6, 6, &[("O/O/O", 2, 1)])),
+            Some("xp2_7".to_string())
+        );
+    }
+
+    #[test]
+    fn counts_the_objects_on_a_board() {
+        let board = board_with(
+            30,
+            20,
+            &[
+                ("OO/OO", 1, 1),
+                ("OO/OO", 10, 1),
+                ("OOO", 20, 2),
+                (".OO./O..O/.OO.", 1, 10),
+                ("OO./O.O/O..", 10, 10),
+                ("OO.O/O.OO", 20, 12),
+            ],
+        );
+
+        let census = CensusTaker::new().take(&board);
+
+        assert_eq!(census.total(), 6);
+        assert_eq!(census.count_of("block"), 2);
+        assert_eq!(census.count_of("blinker"), 1);
+        assert_eq!(census.count_of("beehive"), 1);
+        assert_eq!(census.count_of("glider"), 1);
+        assert_eq!(
+            census.to_string(),
+            "     2  block\n\
+             \x20    1  beehive\n\
+             \x20    1  blinker\n\
+             \x20    1  glider\n\
+             \x20    1  xs6_bd\n"
+        );
+    }
+
+    #[test]
+    fn a_wider_radius_keeps_a_beacon_together() {
+        let beacon = board_with(8, 8, &[("OO../O.../...O/..OO", 2, 2)]);
+
+        assert_eq!(CensusTaker::new().take(&beacon).count_of("block"), 2);
+        assert_eq!(
+            CensusTaker::new()
+                .with_radius(2)
+                .take(&beacon)
+                .count_of("beacon"),
+            1
+        );
+    }
+
+    #[test]
+    fn objects_across_the_edges_of_a_torus_are_counted_once() {
+        let board =
+            board_with(6, 6, &[("O/O", 5, 2), ("O/O", 0, 2)]).with_topology(Topology::Toroidal);
+
+        assert_eq!(CensusTaker::new().take(&board).count_of("block"), 1);
+    }
+}
@@ -0,0 +1,469 @@
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+use board::symmetry::Symmetry;
+use rule::Rule;
+
+pub const USAGE: &str = "\
+Usage: game [OPTIONS] [PATTERN]
+
+Plays Conway's Game of Life, or any other Life-like rule, in the terminal.
+
+Arguments:
+  [PATTERN]                  Pattern file to start from (.rle, .cells or Life 1.06).
+                             Without one, the board starts as a random soup.
+
+Options:
+  -r, --rule <RULE>          Rule in B/S notation, e.g. B36/S23 [default: the
+                             pattern's rule, or B3/S23]
+  -W, --width <CELLS>        Board width [default: fills the terminal]
+  -H, --height <CELLS>       Board height [default: fills the terminal]
+  -t, --topology <TOPOLOGY>  bounded, torus or plane [default: torus]
+  -g, --generations <N>      Number of generations to play [default: 1000]
+  -d, --delay <MS>           Milliseconds to wait between generations [default: 30]
+  -s, --seed <SEED>          Seed for the random soup [default: random]
+      --density <DENSITY>    Chance of each soup cell being alive, 0.0-1.0 [default: 0.5]
+      --symmetry <SYMMETRY>  Symmetry of the random soup: C1, C2, C4, D4 or D8.
+                             C4 and D8 soups fill a centred square [default: C1]
+      --renderer <RENDERER>  string, braille, half-block or none. braille draws
+                             2x4 cells in each character and half-block draws
+                             1x2 in 24-bit colour [default: string]
+      --theme <THEME>        How the string renderer draws cells: classic,
+                             monochrome, high-contrast, colour-blind, or a theme
+                             file. Not for braille or half-block [default: classic]
+      --stop-when-periodic   Stop once the board dies out, starts repeating or
+                             turns out to be a spaceship, and report which
+      --census               Once the game ends, count the objects left on the board
+      --statistics <FILE>    Save the population, births, deaths, bounding box and
+                             density of every generation, as JSON if FILE ends in
+                             .json and as CSV otherwise
+  -i, --interactive          Play until quit, with keyboard controls:
+                             space pauses, n steps, b steps back, +/- change
+                             speed, r reseeds and q quits. While paused, click
+                             or drag with the mouse to draw cells, with the
+                             string renderer only
+  -h, --help                 Print this help";
+
+const OPTIONS_WITH_VALUES: &[&str] = &[
+    "-r",
+    "--rule",
+    "-W",
+    "--width",
+    "-H",
+    "--height",
+    "-t",
+    "--topology",
+    "-g",
+    "--generations",
+    "-d",
+    "--delay",
+    "-s",
+    "--seed",
+    "--density",
+    "--symmetry",
+    "--renderer",
+    "--statistics",
+    "--theme",
+];
+
+/// Where the board's cells live.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Space {
+    /// A rectangle with hard edges.
+    Bounded,
+    /// A rectangle whose edges wrap around.
+    Torus,
+    /// An unbounded plane, viewed through a window the size of the board.
+    Plane,
+}
+
+impl FromStr for Space {
+    type Err = CliError;
+
+    fn from_str(s: &str) -> Result<Space, CliError> {
+        match s {
+            "bounded" => Ok(Space::Bounded),
+            "torus" | "toroidal" => Ok(Space::Torus),
+            "plane" | "unbounded" => Ok(Space::Plane),
+            _ => Err(CliError::new(format!(
+                "unknown topology '{}' (expected bounded, torus or plane)",
+                s
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum RendererKind {
+    String,
+    Braille,
+    HalfBlock,
+    None,
+}
+
+impl RendererKind {
+    /// How many cells across and down the renderer draws in each character.
+    pub fn cells_per_character(self) -> (u16, u16) {
+        match self {
+            RendererKind::Braille => (2, 4),
+            RendererKind::HalfBlock => (1, 2),
+            RendererKind::String | RendererKind::None => (1, 1),
+        }
+    }
+}
+
+impl FromStr for RendererKind {
+    type Err = CliError;
+
+    fn from_str(s: &str) -> Result<RendererKind, CliError> {
+        match s {
+            "string" => Ok(RendererKind::String),
+            "braille" => Ok(RendererKind::Braille),
+            "half-block" => Ok(RendererKind::HalfBlock),
+            "none" => Ok(RendererKind::None),
+            _ => Err(CliError::new(format!(
+                "unknown renderer '{}' (expected string, braille, half-block or none)",
+                s
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Options {
+    pub pattern: Option<PathBuf>,
+    pub rule: Option<Rule>,
+    pub width: Option<u16>,
+    pub height: Option<u16>,
+    pub space: Space,
+    pub generations: u32,
+    pub delay: Duration,
+    pub seed: Option<u64>,
+    pub density: f64,
+    pub symmetry: Symmetry,
+    pub renderer: RendererKind,
+    /// A preset theme's name, or the path of a theme file.
+    pub theme: Option<String>,
+    pub interactive: bool,
+    pub stop_when_periodic: bool,
+    pub census: bool,
+    pub statistics: Option<PathBuf>,
+}
+
+impl Default for Options {
+    fn default() -> Options {
+        Options {
+            pattern: None,
+            rule: None,
+            width: None,
+            height: None,
+            space: Space::Torus,
+            generations: 1000,
+            delay: Duration::from_millis(30),
+            seed: None,
+            density: 0.5,
+            symmetry: Symmetry::C1,
+            renderer: RendererKind::String,
+            theme: None,
+            interactive: false,
+            stop_when_periodic: false,
+            census: false,
+            statistics: None,
+        }
+    }
+}
+
+impl Options {
+    /// The width and height of the board in cells: those given on the
+    /// command line, or else enough to fill a `terminal` of that many
+    /// characters. Bounded and toroidal boards grow to fit a pattern of
+    /// `pattern` cells, and it's an error for a size given on the command
+    /// line to be too small for it. The plane is only viewed through a
+    /// window the size of the board, so any size will do.
+    pub fn board_size(
+        &self,
+        terminal: (u16, u16),
+        pattern: Option<(i32, i32)>,
+    ) -> Result<(u16, u16), CliError> {
+        let (across, down) = self.renderer.cells_per_character();
+        let width = self.width.unwrap_or(terminal.0.saturating_mul(across));
+        let height = self.height.unwrap_or(terminal.1.saturating_mul(down));
+        let (pattern_width, pattern_height) = match (self.space, pattern) {
+            (Space::Plane, _) | (_, None) => return Ok((width, height)),
+            (_, Some(size)) => size,
+        };
+
+        let too_small =
+            |given: Option<u16>, needed: i32| given.is_some_and(|g| i32::from(g) < needed);
+        if too_small(self.width, pattern_width) || too_small(self.height, pattern_height) {
+            return Err(CliError::new(format!(
+                "the pattern is {}x{} cells, which doesn't fit on a {}x{} board",
+                pattern_width, pattern_height, width, height
+            )));
+        }
+        match (u16::try_from(pattern_width), u16::try_from(pattern_height)) {
+            (Ok(pattern_width), Ok(pattern_height)) => {
+                Ok((width.max(pattern_width), height.max(pattern_height)))
+            }
+            _ => Err(CliError::new(format!(
+                "the pattern is {}x{} cells, which is too big for a bounded board",
+                pattern_width, pattern_height
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Command {
+    Run(Options),
+    Help,
+}
+
+/// Parses the arguments that follow the program name.
+pub fn parse<I, S>(args: I) -> Result<Command, CliError>
+where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    let mut options = Options::default();
+    let mut args = args.into_iter().map(|a| a.into());
+
+    while let Some(arg) = args.next() {
+        if !arg.starts_with('-') || arg == "-" {
+            if options.pattern.is_some() {
+                return Err(CliError::new(format!("unexpected argument '{}'", arg)));
+            }
+            options.pattern = Some(PathBuf::from(arg));
+            continue;
+        }
+
+        let (flag, inline_value) = match arg.find('=') {
+            Some(i) if arg.starts_with("--") => {
+                (arg[..i].to_string(), Some(arg[i + 1..].to_string()))
+            }
+            _ => (arg.clone(), None),
+        };
+        if flag == "-h" || flag == "--help" {
+            return Ok(Command::Help);
+        }
+        if flag == "-i" || flag == "--interactive" {
+            options.interactive = true;
+            continue;
+        }
+        if flag == "--stop-when-periodic" {
+            options.stop_when_periodic = true;
+            continue;
+        }
+        if flag == "--census" {
+            options.census = true;
+            continue;
+        }
+        if !OPTIONS_WITH_VALUES.contains(&flag.as_str()) {
+            return Err(CliError::new(format!("unknown option '{}'", flag)));
+        }
+
+        let value = match inline_value.or_else(|| args.next()) {
+            Some(value) => value,
+            None => return Err(CliError::new(format!("'{}' needs a value", flag))),
+        };
+        match flag.as_str() {
+            "-r" | "--rule" => {
+                options.rule = Some(
+                    value
+                        .parse()
+                        .map_err(|e| CliError::new(format!("invalid rule '{}': {}", value, e)))?,
+                )
+            }
+            "-W" | "--width" => options.width = Some(parse_number(&flag, &value)?),
+            "-H" | "--height" => options.height = Some(parse_number(&flag, &value)?),
+            "-t" | "--topology" => options.space = value.parse()?,
+            "-g" | "--generations" => options.generations = parse_number(&flag, &value)?,
+            "-d" | "--delay" => options.delay = Duration::from_millis(parse_number(&flag, &value)?),
+            "-s" | "--seed" => options.seed = Some(parse_number(&flag, &value)?),
+            "--density" => {
+                options.density = parse_number(&flag, &value)?;
+                if !(0.0..=1.0).contains(&options.density) {
+                    return Err(CliError::new(format!(
+                        "'{}' must be between 0.0 and 1.0, found {}",
+                        flag, value
+                    )));
+                }
+            }
+            "--symmetry" => {
+                options.symmetry = value
+                    .parse::<Symmetry>()
+                    .map_err(|e| CliError::new(e.to_string()))?
+            }
+            "--renderer" => options.renderer = value.parse()?,
+            "--statistics" => options.statistics = Some(PathBuf::from(value)),
+            "--theme" => options.theme = Some(value),
+            _ => unreachable!(),
+        }
+    }
+
+    if options.width == Some(0) || options.height == Some(0) {
+        return Err(CliError::new(
+            "the board must be at least one cell wide and high",
+        ));
+    }
+    match (options.renderer, &options.theme) {
+        (RendererKind::Braille, Some(_)) | (RendererKind::HalfBlock, Some(_)) => {
+            return Err(CliError::new(
+                "'--theme' can't be used with the braille or half-block renderers",
+            ))
+        }
+        _ => {}
+    }
+    Ok(Command::Run(options))
+}
+
+fn parse_number<T: FromStr>(flag: &str, value: &str) -> Result<T, CliError> {
+    value
+        .parse()
+        .map_err(|_| CliError::new(format!("invalid value '{}' for '{}'", value, flag)))
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct CliError {
+    message: String,
+}
+
+impl CliError {
+    fn new<S: Into<String>>(message: S) -> CliError {
+        CliError {
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl Error for CliError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(args: &[&str]) -> Options {
+        match parse(args.iter().cloned()) {
+            Ok(Command::Run(options)) => options,
+            other => panic!("expected options, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn no_arguments_gives_the_defaults() {
+        assert_eq!(options(&[]), Options::default());
+    }
+
+    #[test]
+    fn parses_every_option() {
+        let parsed = options(&[
+            "--rule",
+            "B36/S23",
+            "-W",
+            "80",
+            "--height=24",
+            "-t",
+            "plane",
+            "-g",
+            "50",
+            "-d",
+            "0",
+            "--seed",
+            "7",
+            "--density",
+            "0.25",
+            "--symmetry",
+            "D4",
+            "--renderer",
+            "none",
+            "--theme",
+            "monochrome",
+            "--interactive",
+            "--stop-when-periodic",
+            "--census",
+            "--statistics",
+            "stats.csv",
+            "glider.rle",
+        ]);
+
+        assert_eq!(
+            parsed,
+            Options {
+                pattern: Some(PathBuf::from("glider.rle")),
+                rule: Some(Rule::new(&[3, 6], &[2, 3])),
+                width: Some(80),
+                height: Some(24),
+                space: Space::Plane,
+                generations: 50,
+                delay: Duration::from_millis(0),
+                seed: Some(7),
+                density: 0.25,
+                symmetry: Symmetry::D4,
+                renderer: RendererKind::None,
+                theme: Some(String::from("monochrome")),
+                interactive: true,
+                stop_when_periodic: true,
+                census: true,
+                statistics: Some(PathBuf::from("stats.csv")),
+            }
+        );
+    }
+
+    #[test]
+    fn bounded_boards_grow_to_fit_the_pattern() {
+        let torus = options(&["glider.rle"]);
+        let braille = options(&["-t", "bounded", "--renderer", "braille", "-H", "10"]);
+
+        assert_eq!(torus.board_size((80, 24), None), Ok((80, 24)));
+        assert_eq!(torus.board_size((80, 24), Some((3, 3))), Ok((80, 24)));
+        assert_eq!(torus.board_size((80, 24), Some((100, 4))), Ok((100, 24)));
+        assert_eq!(braille.board_size((80, 24), Some((100, 4))), Ok((160, 10)));
+    }
+
+    #[test]
+    fn a_pattern_that_doesnt_fit_the_given_size_is_an_error() {
+        let small = options(&["-W", "10", "-H", "10", "gun.rle"]);
+        let plane = options(&["-W", "10", "-H", "10", "-t", "plane", "gun.rle"]);
+
+        assert_eq!(
+            small.board_size((80, 24), Some((36, 9))),
+            Err(CliError::new(
+                "the pattern is 36x9 cells, which doesn't fit on a 10x10 board"
+            ))
+        );
+        assert_eq!(plane.board_size((80, 24), Some((36, 9))), Ok((10, 10)));
+        assert!(options(&[])
+            .board_size((80, 24), Some((70_000, 1)))
+            .is_err());
+    }
+
+    #[test]
+    fn help_wins_over_everything_else() {
+        assert_eq!(parse(vec!["-g", "5", "--help"]), Ok(Command::Help));
+    }
+
+    #[test]
+    fn rejects_bad_input() {
+        assert!(parse(vec!["--generations", "lots"]).is_err());
+        assert!(parse(vec!["--rule", "B9/S"]).is_err());
+        assert!(parse(vec!["--density", "1.5"]).is_err());
+        assert!(parse(vec!["--topology", "sphere"]).is_err());
+        assert!(parse(vec!["--renderer", "smoke-signals"]).is_err());
+        assert!(parse(vec!["--symmetry", "C3"]).is_err());
+        assert!(parse(vec!["--width", "0"]).is_err());
+        assert!(parse(vec!["--renderer", "braille", "--theme", "monochrome"]).is_err());
+        assert!(parse(vec!["--frobnicate", "1"]).is_err());
+        assert!(parse(vec!["--seed"]).is_err());
+        assert!(parse(vec!["a.rle", "b.rle"]).is_err());
+    }
+}
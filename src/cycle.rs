@@ -0,0 +1,604 @@
+use std::cmp;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+use interface::{Board, Control, Observer};
+use models::{BoundingBox, Coordinates};
+
+/// What a board has settled into.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Classification {
+    /// Every cell is dead.
+    Extinct,
+    /// Each generation is the same as the last.
+    Static,
+    /// The board repeats itself every `period` generations.
+    Oscillating { period: u32 },
+    /// The board repeats itself every `period` generations, moved `dx`
+    /// cells right and `dy` cells down.
+    Spaceship { period: u32, dx: i32, dy: i32 },
+}
+
+impl Classification {
+    /// How fast a spaceship moves.
+    pub fn velocity(&self) -> Option<Velocity> {
+        match *self {
+            Classification::Spaceship { period, dx, dy } => Some(Velocity::new(dx, dy, period)),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Classification {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Classification::Extinct => write!(f, "extinct"),
+            Classification::Static => write!(f, "static"),
+            Classification::Oscillating { period } => {
+                write!(f, "oscillating with period {}", period)
+            }
+            Classification::Spaceship { period, dx, dy } => write!(
+                f,
+                "spaceship with period {} moving ({}, {}) at {}",
+                period,
+                dx,
+                dy,
+                Velocity::new(dx, dy, period)
+            ),
+        }
+    }
+}
+
+/// A spaceship's speed as a fraction of c, the speed of light, which is one
+/// cell per generation.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Velocity {
+    /// Cells moved along the longer axis, in lowest terms with
+    /// `generations`.
+    pub cells: u32,
+    pub generations: u32,
+    pub direction: Direction,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Direction {
+    Orthogonal,
+    Diagonal,
+    Oblique,
+}
+
+impl Velocity {
+    pub fn new(dx: i32, dy: i32, period: u32) -> Velocity {
+        let cells = cmp::max(dx.unsigned_abs(), dy.unsigned_abs());
+        let divisor = gcd(cells, period).max(1);
+        let direction = if dx == 0 || dy == 0 {
+            Direction::Orthogonal
+        } else if dx.abs() == dy.abs() {
+            Direction::Diagonal
+        } else {
+            Direction::Oblique
+        };
+        Velocity {
+            cells: cells / divisor,
+            generations: period / divisor,
+            direction,
+        }
+    }
+}
+
+impl fmt::Display for Velocity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.cells != 1 {
+            write!(f, "{}", self.cells)?;
+        }
+        write!(f, "c")?;
+        if self.generations != 1 {
+            write!(f, "/{}", self.generations)?;
+        }
+        let direction = match self.direction {
+            Direction::Orthogonal => "orthogonal",
+            Direction::Diagonal => "diagonal",
+            Direction::Oblique => "oblique",
+        };
+        write!(f, " {}", direction)
+    }
+}
+
+fn gcd(a: u32, b: u32) -> u32 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// Watches a game generation by generation, and notices when the board
+/// repeats a set of living cells it has had before, either in the same place
+/// or moved somewhere else.
+///
+/// Each generation is remembered by a 64 bit hash of its living cells,
+/// translated so that their top left corner is at the origin, along with
+/// where that corner was, so the history takes a few bytes a generation
+/// however big the board is. A board whose hash matches an earlier
+/// generation's has repeated, in the same place or moved somewhere else.
+/// Two different sets of cells only share a hash by a chance of about one
+/// in 2^64, which is trusted as never happening.
+///
+/// On a torus there are no edges to find a corner by, so the columns and
+/// rows are counted instead and the corner is put where those counts, read
+/// all the way round, come first in order. When several corners tie, as
+/// they do for evenly spaced objects, the one whose translated cells come
+/// first in order is used, and a move between generations is measured the
+/// shortest way to any of the corners that tie with it.
+///
+/// As an observer of a game, it stops the game once the board is
+/// classified.
+#[derive(Debug, Default)]
+pub struct CycleDetector {
+    generation: u32,
+    shapes: HashMap<u64, (u32, Coordinates)>,
+    classification: Option<Classification>,
+}
+
+impl CycleDetector {
+    pub fn new() -> CycleDetector {
+        CycleDetector::default()
+    }
+
+    /// How many generations have been observed.
+    pub fn generations(&self) -> u32 {
+        self.generation
+    }
+
+    /// What the board settled into, once it has.
+    pub fn classification(&self) -> Option<Classification> {
+        self.classification
+    }
+
+    /// Records the next generation, classifying the board as soon as it
+    /// dies out or repeats.
+    pub fn observe<T: Board>(&mut self, board: &T) -> Option<Classification> {
+        let classification = self.classify_next(board);
+        if classification.is_some() {
+            self.classification = classification;
+        }
+        classification
+    }
+
+    fn classify_next<T: Board>(&mut self, board: &T) -> Option<Classification> {
+        let generation = self.generation;
+        self.generation += 1;
+
+        if board.population() == 0 {
+            return Some(Classification::Extinct);
+        }
+        let cells: Vec<Coordinates> = board.live_cells().collect();
+        let (shape, origins) = normalise(&cells, board.torus_size());
+        let (previous, previous_origin) = self
+            .shapes
+            .insert(hash_of(&shape), (generation, origins[0]))?;
+
+        let (dx, dy) = origins
+            .iter()
+            .map(|origin| {
+                let (dx, dy) = (origin.x - previous_origin.x, origin.y - previous_origin.y);
+                match board.torus_size() {
+                    Some((width, height)) => (shortest(dx, width), shortest(dy, height)),
+                    None => (dx, dy),
+                }
+            })
+            .min_by_key(|&(dx, dy)| (dx.abs() + dy.abs(), dx, dy))
+            .unwrap_or((0, 0));
+        Some(match (generation - previous, dx, dy) {
+            (1, 0, 0) => Classification::Static,
+            (period, 0, 0) => Classification::Oscillating { period },
+            (period, dx, dy) => Classification::Spaceship { period, dx, dy },
+        })
+    }
+}
+
+impl<T: Board> Observer<T> for CycleDetector {
+    fn observe(&mut self, _: u32, board: &T) -> Control {
+        match CycleDetector::observe(self, board) {
+            Some(_) => Control::Stop,
+            None => Control::Continue,
+        }
+    }
+}
+
+/// The shape of a set of cells regardless of where it is, as the cells
+/// measured from a corner in order row by row, along with every corner that
+/// gives that shape, first the one it was measured from.
+fn normalise(
+    cells: &[Coordinates],
+    torus_size: Option<(i32, i32)>,
+) -> (Vec<Coordinates>, Vec<Coordinates>) {
+    let (width, height) = match torus_size {
+        Some(size) => size,
+        None => {
+            let origin = match BoundingBox::around(cells.iter().cloned()) {
+                Some(bounds) => bounds.min,
+                None => Coordinates { x: 0, y: 0 },
+            };
+            return (translate(cells, origin, None), vec![origin]);
+        }
+    };
+
+    let columns = least_rotations(&counts(cells.iter().map(|c| c.x), width));
+    let rows = least_rotations(&counts(cells.iter().map(|c| c.y), height));
+    let mut shape: Option<Vec<Coordinates>> = None;
+    let mut origins = Vec::new();
+    for &y in &rows {
+        for &x in &columns {
+            let origin = Coordinates {
+                x: x as i32,
+                y: y as i32,
+            };
+            let translated = translate(cells, origin, torus_size);
+            let order = shape.as_ref().map(|shape| {
+                let key = |c: &Coordinates| (c.y, c.x);
+                translated.iter().map(key).cmp(shape.iter().map(key))
+            });
+            match order {
+                Some(cmp::Ordering::Greater) => {}
+                Some(cmp::Ordering::Equal) => origins.push(origin),
+                Some(cmp::Ordering::Less) | None => {
+                    shape = Some(translated);
+                    origins = vec![origin];
+                }
+            }
+        }
+    }
+    (shape.unwrap_or_default(), origins)
+}
+
+/// The cells measured from `origin` in order row by row, wrapping around
+/// the edges of a torus.
+fn translate(
+    cells: &[Coordinates],
+    origin: Coordinates,
+    torus_size: Option<(i32, i32)>,
+) -> Vec<Coordinates> {
+    let (width, height) = torus_size.unwrap_or((i32::MAX, i32::MAX));
+    let mut translated: Vec<Coordinates> = cells
+        .iter()
+        .map(|c| Coordinates {
+            x: (c.x - origin.x).rem_euclid(width),
+            y: (c.y - origin.y).rem_euclid(height),
+        })
+        .collect();
+    translated.sort_by_key(|c| (c.y, c.x));
+    translated
+}
+
+fn hash_of(shape: &[Coordinates]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    shape.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// How many of `positions` fall on each of the `length` positions of an axis
+/// that wraps around.
+fn counts<I: Iterator<Item = i32>>(positions: I, length: i32) -> Vec<u32> {
+    let mut counts = vec![0; length as usize];
+    for position in positions {
+        counts[position.rem_euclid(length) as usize] += 1;
+    }
+    counts
+}
+
+/// Where to start reading `values` all the way round so that they come
+/// first in lexicographical order.
+fn least_rotation(values: &[u32]) -> usize {
+    let length = values.len();
+    let (mut i, mut j, mut k) = (0, 1, 0);
+    while i < length && j < length && k < length {
+        let (a, b) = (values[(i + k) % length], values[(j + k) % length]);
+        if a == b {
+            k += 1;
+            continue;
+        }
+        if a > b {
+            i += k + 1;
+        } else {
+            j += k + 1;
+        }
+        if i == j {
+            j += 1;
+        }
+        k = 0;
+    }
+    cmp::min(i, j)
+}
+
+/// Every place to start reading `values` all the way round so that they
+/// come first in lexicographical order, which are the least rotation and
+/// those a whole number of the values' periods after it.
+fn least_rotations(values: &[u32]) -> Vec<usize> {
+    let length = values.len();
+    let period = (1..=length)
+        .find(|&p| {
+            length.is_multiple_of(p) && (0..length).all(|i| values[i] == values[(i + p) % length])
+        })
+        .unwrap_or(length.max(1));
+    let first = least_rotation(values);
+    (first..length.max(1)).step_by(period).collect()
+}
+
+/// The distance `d` along an axis of `length` positions that wraps around,
+/// taking the short way round.
+fn shortest(d: i32, length: i32) -> i32 {
+    let d = d.rem_euclid(length);
+    if d > length / 2 {
+        d - length
+    } else {
+        d
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use board::grid::GridBoard;
+    use board::sparse::SparseBoard;
+    use board::Topology;
+    use generator::SimpleGenerator;
+    use interface::Generator;
+    use models::CellState;
+
+    fn classify<T: Board>(board: T, generations: u32) -> Option<(Classification, u32)> {
+        let generator = SimpleGenerator::new();
+        let mut detector = CycleDetector::new();
+        let mut board = board;
+        for _ in 0..generations {
+            if let Some(classification) = detector.observe(&board) {
+                return Some((classification, detector.generations() - 1));
+            }
+            board = generator.generate(&board);
+        }
+        None
+    }
+
+    const GLIDER: [(i32, i32); 5] = [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)];
+
+    fn board_with(cells: &[(i32, i32)]) -> GridBoard {
+        let cells: Vec<Coordinates> = cells.iter().map(|&(x, y)| Coordinates { x, y }).collect();
+        GridBoard::fill_with(
+            8,
+            8,
+            Box::new(move |c| {
+                if cells.contains(&c) {
+                    CellState::Alive
+                } else {
+                    CellState::Dead
+                }
+            }),
+        )
+    }
+
+    #[test]
+    fn an_empty_board_is_extinct() {
+        assert_eq!(
+            classify(GridBoard::fill_with(3, 3, Box::new(|_| CellState::Dead)), 5),
+            Some((Classification::Extinct, 0))
+        );
+    }
+
+    #[test]
+    fn a_lone_cell_dies_out() {
+        assert_eq!(
+            classify(board_with(&[(3, 3)]), 5),
+            Some((Classification::Extinct, 1))
+        );
+    }
+
+    #[test]
+    fn a_block_is_static() {
+        assert_eq!(
+            classify(board_with(&[(3, 3), (4, 3), (3, 4), (4, 4)]), 5),
+            Some((Classification::Static, 1))
+        );
+    }
+
+    #[test]
+    fn a_blinker_oscillates() {
+        assert_eq!(
+            classify(board_with(&[(2, 3), (3, 3), (4, 3)]), 5),
+            Some((Classification::Oscillating { period: 2 }, 2))
+        );
+    }
+
+    #[test]
+    fn a_pattern_that_settles_down_is_classified_once_it_repeats() {
+        // A pre-block becomes a block.
+        let pre_block = SparseBoard::with_live_cells(vec![
+            Coordinates { x: 0, y: 0 },
+            Coordinates { x: 1, y: 0 },
+            Coordinates { x: 0, y: 1 },
+        ]);
+
+        assert_eq!(classify(pre_block, 5), Some((Classification::Static, 2)));
+    }
+
+    #[test]
+    fn a_glider_is_a_spaceship() {
+        let glider =
+            SparseBoard::with_live_cells(GLIDER.iter().map(|&(x, y)| Coordinates { x, y }));
+
+        assert_eq!(
+            classify(glider, 10),
+            Some((
+                Classification::Spaceship {
+                    period: 4,
+                    dx: 1,
+                    dy: 1
+                },
+                4
+            ))
+        );
+    }
+
+    #[test]
+    fn a_spaceship_straddling_the_edges_of_a_torus_is_recognised() {
+        let cells: Vec<(i32, i32)> = GLIDER
+            .iter()
+            .map(|&(x, y)| ((x + 6) % 8, (y + 7) % 8))
+            .collect();
+        let glider = board_with(&cells).with_topology(Topology::Toroidal);
+
+        assert_eq!(
+            classify(glider, 10),
+            Some((
+                Classification::Spaceship {
+                    period: 4,
+                    dx: 1,
+                    dy: 1
+                },
+                4
+            ))
+        );
+    }
+
+    #[test]
+    fn evenly_spaced_objects_on_a_torus_are_recognised() {
+        let blinkers = GridBoard::fill_with(
+            12,
+            6,
+            Box::new(|c| {
+                if c.y == 2 && (1..4).contains(&(c.x % 6)) {
+                    CellState::Alive
+                } else {
+                    CellState::Dead
+                }
+            }),
+        )
+        .with_topology(Topology::Toroidal);
+
+        assert_eq!(
+            classify(blinkers, 5),
+            Some((Classification::Oscillating { period: 2 }, 2))
+        );
+    }
+
+    #[test]
+    fn evenly_spaced_spaceships_on_a_torus_move_the_shortest_way() {
+        for shift in 0..8 {
+            let gliders = GridBoard::fill_with(
+                16,
+                16,
+                Box::new(move |c| {
+                    let (x, y) = ((c.x + shift) % 16, (c.y + shift) % 16);
+                    if x / 8 == y / 8 && GLIDER.contains(&(x % 8, y % 8)) {
+                        CellState::Alive
+                    } else {
+                        CellState::Dead
+                    }
+                }),
+            )
+            .with_topology(Topology::Toroidal);
+
+            assert_eq!(
+                classify(gliders, 10),
+                Some((
+                    Classification::Spaceship {
+                        period: 4,
+                        dx: 1,
+                        dy: 1
+                    },
+                    4
+                )),
+                "shifted by {}",
+                shift
+            );
+        }
+    }
+
+    #[test]
+    fn ties_between_corners_on_a_torus_are_broken_by_the_cells() {
+        // Every row and column holds one cell, so the counts tie everywhere.
+        let cells = vec![
+            Coordinates { x: 0, y: 0 },
+            Coordinates { x: 2, y: 1 },
+            Coordinates { x: 1, y: 2 },
+            Coordinates { x: 3, y: 3 },
+        ];
+        let moved: Vec<Coordinates> = cells
+            .iter()
+            .map(|c| Coordinates {
+                x: (c.x + 1) % 4,
+                y: c.y,
+            })
+            .collect();
+
+        let (shape, origins) = normalise(&cells, Some((4, 4)));
+        let (moved_shape, moved_origins) = normalise(&moved, Some((4, 4)));
+
+        assert_eq!(shape, moved_shape);
+        assert_eq!(origins.len(), 1);
+        assert_eq!(moved_origins[0].x, (origins[0].x + 1) % 4);
+    }
+
+    #[test]
+    fn a_lightweight_spaceship_moves_at_half_the_speed_of_light() {
+        let lwss = SparseBoard::with_live_cells(
+            [
+                (1, 0),
+                (4, 0),
+                (0, 1),
+                (0, 2),
+                (4, 2),
+                (0, 3),
+                (1, 3),
+                (2, 3),
+                (3, 3),
+            ]
+            .iter()
+            .map(|&(x, y)| Coordinates { x, y }),
+        );
+
+        let (classification, _) = classify(lwss, 10).unwrap();
+
+        assert_eq!(
+            classification,
+            Classification::Spaceship {
+                period: 4,
+                dx: -2,
+                dy: 0
+            }
+        );
+        assert_eq!(
+            classification.velocity().unwrap().to_string(),
+            "c/2 orthogonal"
+        );
+    }
+
+    #[test]
+    fn velocities_are_in_lowest_terms() {
+        assert_eq!(Velocity::new(1, 1, 4).to_string(), "c/4 diagonal");
+        assert_eq!(Velocity::new(0, -3, 3).to_string(), "c orthogonal");
+        assert_eq!(Velocity::new(2, 0, 5).to_string(), "2c/5 orthogonal");
+        assert_eq!(Velocity::new(2, 1, 6).to_string(), "c/3 oblique");
+        assert_eq!(Classification::Static.velocity(), None);
+    }
+
+    #[test]
+    fn rotations_wrap_around_the_edges() {
+        assert_eq!(least_rotation(&counts(vec![0, 1, 7].into_iter(), 8)), 2);
+        assert_eq!(least_rotation(&[1, 0, 0, 1, 0, 0]), 1);
+        assert_eq!(least_rotation(&[2]), 0);
+        assert_eq!(least_rotations(&[1, 0, 0, 1, 0, 0]), vec![1, 4]);
+        assert_eq!(least_rotations(&[2]), vec![0]);
+        assert_eq!(shortest(7, 8), -1);
+        assert_eq!(shortest(-7, 8), 1);
+    }
+
+    #[test]
+    fn the_order_of_the_cells_does_not_matter() {
+        let cells = vec![Coordinates { x: 1, y: 2 }, Coordinates { x: 5, y: -3 }];
+        let reversed = cells.iter().rev().cloned().collect::<Vec<_>>();
+
+        assert_eq!(normalise(&cells, None), normalise(&reversed, None));
+    }
+}
@@ -1,10 +1,11 @@
 use models::*;
 use models::CellState::*;
+use rule::Rule;
 
-pub fn process(cell: &Cell, neighbours: Vec<CellState>) -> Cell {
+pub fn process(rule: &Rule, cell: &Cell, neighbours: Vec<CellState>) -> Cell {
     let cell_state = match cell.cell_state {
-        Died | Dead => process_dead_cell(neighbours),
-        Born | Alive => process_living_cell(neighbours),
+        Died | Dead => process_dead_cell(rule, neighbours),
+        Born | Alive => process_living_cell(rule, neighbours),
     };
 
     Cell {
@@ -13,18 +14,19 @@ pub fn process(cell: &Cell, neighbours: Vec<CellState>) -> Cell {
     }
 }
 
-fn process_dead_cell(neighbours: Vec<CellState>) -> CellState {
-    if count_of_living_neighbours(neighbours) == 3 {
+fn process_dead_cell(rule: &Rule, neighbours: Vec<CellState>) -> CellState {
+    if rule.is_born(count_of_living_neighbours(neighbours)) {
         Born
     } else {
         Dead
     }
 }
 
-fn process_living_cell(neighbours: Vec<CellState>) -> CellState {
-    match count_of_living_neighbours(neighbours) {
-        x if x < 2 || x > 3 => Died,
-        _ => Alive,
+fn process_living_cell(rule: &Rule, neighbours: Vec<CellState>) -> CellState {
+    if rule.survives(count_of_living_neighbours(neighbours)) {
+        Alive
+    } else {
+        Died
     }
 }
 
@@ -46,7 +48,7 @@ mod tests {
         };
         let neighbours = vec![];
 
-        let processed_cell = process(&cell, neighbours);
+        let processed_cell = process(&Rule::conway(), &cell, neighbours);
 
         assert_eq!(processed_cell.cell_state, Dead);
     }
@@ -59,7 +61,7 @@ mod tests {
         };
         let neighbours = vec![Dead, Dead, Dead, Dead, Dead, Dead, Dead];
 
-        let processed_cell = process(&cell, neighbours);
+        let processed_cell = process(&Rule::conway(), &cell, neighbours);
 
         assert_eq!(processed_cell.cell_state, Dead);
     }
@@ -72,7 +74,7 @@ mod tests {
         };
         let neighbours = vec![Alive, Alive, Dead, Dead, Dead, Dead, Dead];
 
-        let processed_cell = process(&cell, neighbours);
+        let processed_cell = process(&Rule::conway(), &cell, neighbours);
 
         assert_eq!(processed_cell.cell_state, Dead);
     }
@@ -85,7 +87,7 @@ mod tests {
         };
         let neighbours = vec![Alive, Alive, Alive, Dead, Dead, Dead, Dead];
 
-        let processed_cell = process(&cell, neighbours);
+        let processed_cell = process(&Rule::conway(), &cell, neighbours);
 
         assert_eq!(processed_cell.cell_state, Born);
     }
@@ -98,7 +100,7 @@ mod tests {
         };
         let neighbours = vec![Born, Born, Alive, Dead, Dead, Dead, Dead];
 
-        let processed_cell = process(&cell, neighbours);
+        let processed_cell = process(&Rule::conway(), &cell, neighbours);
 
         assert_eq!(processed_cell.cell_state, Born);
     }
@@ -111,7 +113,7 @@ mod tests {
         };
         let neighbours = vec![Alive, Alive, Alive, Alive, Alive, Dead, Dead];
 
-        let processed_cell = process(&cell, neighbours);
+        let processed_cell = process(&Rule::conway(), &cell, neighbours);
 
         assert_eq!(processed_cell.cell_state, Dead);
     }
@@ -124,7 +126,7 @@ mod tests {
         };
         let neighbours = vec![];
 
-        let processed_cell = process(&cell, neighbours);
+        let processed_cell = process(&Rule::conway(), &cell, neighbours);
 
         assert_eq!(processed_cell.cell_state, Died);
     }
@@ -137,7 +139,7 @@ mod tests {
         };
         let neighbours = vec![Alive];
 
-        let processed_cell = process(&cell, neighbours);
+        let processed_cell = process(&Rule::conway(), &cell, neighbours);
 
         assert_eq!(processed_cell.cell_state, Died);
     }
@@ -151,8 +153,8 @@ mod tests {
         let neighbours = vec![Alive, Alive];
         let neighbours2 = vec![Alive, Alive, Alive];
 
-        let processed_cell = process(&cell, neighbours);
-        let processed_cell2 = process(&cell, neighbours2);
+        let processed_cell = process(&Rule::conway(), &cell, neighbours);
+        let processed_cell2 = process(&Rule::conway(), &cell, neighbours2);
 
         assert_eq!(processed_cell.cell_state, Alive);
         assert_eq!(processed_cell2.cell_state, Alive);
@@ -166,9 +168,36 @@ mod tests {
         };
         let neighbours = vec![Alive, Alive, Alive, Alive];
 
-        let processed_cell = process(&cell, neighbours);
+        let processed_cell = process(&Rule::conway(), &cell, neighbours);
 
         assert_eq!(processed_cell.cell_state, Died);
     }
 
+    #[test]
+    fn the_rule_decides_which_counts_cause_birth() {
+        let highlife = Rule::new(&[3, 6], &[2, 3]);
+        let cell = Cell {
+            cell_state: Dead,
+            location: Coordinates { x: 0, y: 0 },
+        };
+        let neighbours = vec![Alive, Alive, Alive, Alive, Alive, Alive, Dead];
+
+        let processed_cell = process(&highlife, &cell, neighbours);
+
+        assert_eq!(processed_cell.cell_state, Born);
+    }
+
+    #[test]
+    fn the_rule_decides_which_counts_allow_survival() {
+        let seeds = Rule::new(&[2], &[]);
+        let cell = Cell {
+            cell_state: Alive,
+            location: Coordinates { x: 0, y: 0 },
+        };
+        let neighbours = vec![Alive, Alive];
+
+        let processed_cell = process(&seeds, &cell, neighbours);
+
+        assert_eq!(processed_cell.cell_state, Died);
+    }
 }
@@ -0,0 +1,349 @@
+use std::cell::RefCell;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use rayon::prelude::*;
+
+use super::neighbour_states;
+use engine::process;
+use interface::{Board, Generator};
+use models::Coordinates;
+use rule::Rule;
+
+const DEFAULT_TILE_SIZE: usize = 8;
+
+/// A generator that only recomputes the parts of the board where something
+/// could happen.
+///
+/// The board is split into square tiles. A cell can only change if one of
+/// its neighbours, or the cell itself, was born or died in the last
+/// generation, so only the tiles holding such cells are recomputed and the
+/// rest are copied across untouched.
+///
+/// Which cells were born or died is read from the board itself. That's only
+/// the whole story for boards this generator produced, so it remembers a
+/// hash of the last one. Any other board, such as a fresh soup or one that's
+/// been drawn on, is recomputed in full.
+pub struct ActiveRegionGenerator {
+    rule: Rule,
+    tile_size: usize,
+    last: RefCell<Option<u64>>,
+}
+
+impl Default for ActiveRegionGenerator {
+    fn default() -> ActiveRegionGenerator {
+        ActiveRegionGenerator::new()
+    }
+}
+
+impl ActiveRegionGenerator {
+    pub fn new() -> ActiveRegionGenerator {
+        ActiveRegionGenerator::with_rule(Rule::conway())
+    }
+
+    pub fn with_rule(rule: Rule) -> ActiveRegionGenerator {
+        ActiveRegionGenerator {
+            rule,
+            tile_size: DEFAULT_TILE_SIZE,
+            last: RefCell::new(None),
+        }
+    }
+
+    /// Changes the width and height of the tiles, in cells.
+    ///
+    /// Panics if `tile_size` is zero.
+    pub fn with_tile_size(self, tile_size: usize) -> ActiveRegionGenerator {
+        assert!(tile_size > 0, "tiles must be at least one cell wide");
+        ActiveRegionGenerator { tile_size, ..self }
+    }
+
+    /// The tiles of the board that need recomputing: those next to a cell
+    /// that was born or died on a board this generator produced, or all of
+    /// them for a board from elsewhere.
+    fn active_tiles<T: Board>(&self, board: &T) -> Tiles {
+        let rows = board.rows();
+        let width = rows.first().map_or(0, |row| row.len());
+        let mut tiles = Tiles::new(width, rows.len(), self.tile_size);
+        if *self.last.borrow() != Some(fingerprint(board)) {
+            tiles.activate_all();
+            return tiles;
+        }
+
+        let origin = match rows.first().and_then(|row| row.first()) {
+            Some(cell) => cell.location,
+            None => return tiles,
+        };
+        for cell in board.changed_cells() {
+            let (x, y) = (cell.location.x - origin.x, cell.location.y - origin.y);
+            if x >= 0 && y >= 0 && tiles.is_inside(x as usize, y as usize) {
+                tiles.activate(x as usize, y as usize);
+            } else {
+                activate_neighbourhood(board, cell.location, origin, &mut tiles);
+            }
+        }
+        tiles
+    }
+}
+
+/// A hash of everything about a board that its next generation depends on.
+fn fingerprint<T: Board>(board: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    board.torus_size().hash(&mut hasher);
+    board.rows().hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Activates the tiles of a cell and all its neighbours, which may be in
+/// other tiles or, on a torus, on the other side of the board.
+fn activate_neighbourhood<T: Board>(
+    board: &T,
+    location: Coordinates,
+    origin: Coordinates,
+    tiles: &mut Tiles,
+) {
+    let mut neighbourhood = location.neighbours();
+    neighbourhood.push(location);
+    for neighbour in neighbourhood.into_iter().filter_map(|n| board.at(n)) {
+        let x = neighbour.location.x - origin.x;
+        let y = neighbour.location.y - origin.y;
+        if x >= 0 && y >= 0 && (x as usize) < tiles.width && (y as usize) < tiles.height {
+            tiles.activate(x as usize, y as usize);
+        }
+    }
+}
+
+impl<T: Board> Generator<T> for ActiveRegionGenerator {
+    fn generate(&self, board: &T) -> T {
+        let tiles = self.active_tiles(board);
+        let rule = self.rule;
+        let mut new_rows = board.rows().clone();
+        new_rows.par_iter_mut().enumerate().for_each(|(y, row)| {
+            for x in tiles.active_columns(y) {
+                row[x] = process(&rule, &row[x], neighbour_states(board, &row[x]));
+            }
+        });
+
+        let next = board.successor(new_rows);
+        *self.last.borrow_mut() = Some(fingerprint(&next));
+        next
+    }
+}
+
+/// Which tiles of a board need recomputing.
+struct Tiles {
+    width: usize,
+    height: usize,
+    size: usize,
+    columns: usize,
+    active: Vec<bool>,
+}
+
+impl Tiles {
+    fn new(width: usize, height: usize, size: usize) -> Tiles {
+        let columns = width.div_ceil(size);
+        let rows = height.div_ceil(size);
+        Tiles {
+            width,
+            height,
+            size,
+            columns,
+            active: vec![false; columns * rows],
+        }
+    }
+
+    fn index(&self, x: usize, y: usize) -> usize {
+        (y / self.size) * self.columns + x / self.size
+    }
+
+    /// Whether all the neighbours of the cell at `x`, `y` are in the same
+    /// tile as it.
+    fn is_inside(&self, x: usize, y: usize) -> bool {
+        let inside = |i: usize, length: usize| {
+            !i.is_multiple_of(self.size) && i % self.size != self.size - 1 && i + 1 < length
+        };
+        inside(x, self.width) && inside(y, self.height)
+    }
+
+    fn activate(&mut self, x: usize, y: usize) {
+        let index = self.index(x, y);
+        self.active[index] = true;
+    }
+
+    fn activate_all(&mut self) {
+        for tile in &mut self.active {
+            *tile = true;
+        }
+    }
+
+    /// The columns of row `y` that fall in active tiles.
+    fn active_columns(&self, y: usize) -> Vec<usize> {
+        let first = (y / self.size) * self.columns;
+        (0..self.columns)
+            .filter(|&column| self.active[first + column])
+            .flat_map(|column| column * self.size..((column + 1) * self.size).min(self.width))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use board::grid::GridBoard;
+    use board::sparse::SparseBoard;
+    use board::Topology;
+    use generator::SimpleGenerator;
+    use models::CellState;
+
+    fn assert_matches_simple<T: Board + PartialEq + ::std::fmt::Debug>(board: T) {
+        let simple = SimpleGenerator::new();
+        let active = ActiveRegionGenerator::new().with_tile_size(8);
+        let mut expected = board.clone();
+        let mut actual = board;
+
+        for generation in 0..60 {
+            expected = simple.generate(&expected);
+            actual = active.generate(&actual);
+            assert_eq!(
+                actual,
+                expected,
+                "differs after {} generations",
+                generation + 1
+            );
+        }
+    }
+
+    #[test]
+    fn matches_the_simple_generator_on_a_bounded_board() {
+        assert_matches_simple(GridBoard::seeded_random(37, 29, 3, 0.4));
+    }
+
+    #[test]
+    fn matches_the_simple_generator_on_a_torus() {
+        assert_matches_simple(
+            GridBoard::seeded_random(37, 29, 4, 0.4).with_topology(Topology::Toroidal),
+        );
+    }
+
+    #[test]
+    fn matches_the_simple_generator_on_an_unbounded_board() {
+        let soup = GridBoard::seeded_random(12, 12, 5, 0.5);
+        assert_matches_simple(SparseBoard::with_live_cells(soup.live_cells()));
+    }
+
+    fn active_tile_count<T: Board>(generator: &ActiveRegionGenerator, board: &T) -> usize {
+        generator
+            .active_tiles(board)
+            .active
+            .iter()
+            .filter(|&&active| active)
+            .count()
+    }
+
+    fn blinker_in_the_corner() -> GridBoard {
+        blinker_in_the_corner_of(32)
+    }
+
+    fn blinker_in_the_corner_of(size: i32) -> GridBoard {
+        GridBoard::fill_with(
+            size,
+            size,
+            Box::new(|c| {
+                if c.y == 3 && c.x >= 2 && c.x <= 4 {
+                    CellState::Alive
+                } else {
+                    CellState::Dead
+                }
+            }),
+        )
+    }
+
+    #[test]
+    fn only_tiles_near_changes_are_recomputed() {
+        let generator = ActiveRegionGenerator::new().with_tile_size(8);
+        let board = blinker_in_the_corner();
+        assert_eq!(active_tile_count(&generator, &board), 16);
+
+        let next = generator.generate(&board);
+
+        assert_eq!(active_tile_count(&generator, &next), 1);
+    }
+
+    #[test]
+    fn cells_edited_between_generations_are_picked_up() {
+        let simple = SimpleGenerator::new();
+        let active = ActiveRegionGenerator::new().with_tile_size(8);
+        let mut expected = blinker_in_the_corner();
+        let mut actual = expected.clone();
+
+        for generation in 0..10 {
+            expected = simple.generate(&expected);
+            actual = active.generate(&actual);
+            assert_eq!(actual, expected, "differs after {} edits", generation);
+
+            let x = 12 + 2 * generation;
+            for board in [&mut expected, &mut actual] {
+                board.set(Coordinates { x, y: 20 }, CellState::Alive);
+                board.rows[21][x as usize].cell_state = CellState::Alive;
+            }
+        }
+    }
+
+    #[test]
+    fn work_depends_on_the_active_area_not_the_board_size() {
+        let recomputed = |size| {
+            let generator = ActiveRegionGenerator::new().with_tile_size(8);
+            let next = generator.generate(&blinker_in_the_corner_of(size));
+            let tiles = generator.active_tiles(&next);
+            (0..size as usize)
+                .map(|y| tiles.active_columns(y).len())
+                .sum::<usize>()
+        };
+
+        assert_eq!(recomputed(32), 64);
+        assert_eq!(recomputed(1024), 64);
+    }
+
+    #[test]
+    fn changes_on_a_tile_edge_wake_the_neighbouring_tiles() {
+        let generator = ActiveRegionGenerator::new().with_tile_size(4);
+
+        let next = generator.generate(&blinker_in_the_corner());
+
+        // The blinker now stands from (3, 2) to (3, 4), with cells dying at
+        // (2, 3) and (4, 3) on the edges of four tiles.
+        assert_eq!(active_tile_count(&generator, &next), 4);
+    }
+
+    #[test]
+    fn a_settled_board_is_copied() {
+        let generator = ActiveRegionGenerator::new().with_tile_size(2);
+        let block = GridBoard::fill_with(
+            6,
+            6,
+            Box::new(|c| {
+                if (2..4).contains(&c.x) && (2..4).contains(&c.y) {
+                    CellState::Alive
+                } else {
+                    CellState::Dead
+                }
+            }),
+        );
+
+        let next = generator.generate(&block);
+
+        assert_eq!(next, block);
+        assert_eq!(active_tile_count(&generator, &next), 0);
+    }
+
+    #[test]
+    fn boards_changed_by_hand_are_recomputed_in_full() {
+        let generator = ActiveRegionGenerator::new().with_tile_size(8);
+        let mut board = generator.generate(&blinker_in_the_corner());
+
+        board.set(Coordinates { x: 20, y: 20 }, CellState::Alive);
+
+        assert_eq!(active_tile_count(&generator, &board), 16);
+        let next = generator.generate(&board);
+        assert_eq!(next.rows[20][20].cell_state, CellState::Died);
+    }
+}
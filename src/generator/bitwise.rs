@@ -0,0 +1,285 @@
+//! A generator for `BitBoard`s that works on 64 cells at a time.
+//!
+//! Each of a cell's eight neighbours is lined up with it by shifting the
+//! packed rows above, below and around it, and the eight neighbour words are
+//! summed with full adders into a four bit count per cell. The rule then
+//! becomes a handful of bitwise operations on the count's bits.
+
+use rayon::prelude::*;
+
+use board::bit::BitBoard;
+use board::Topology;
+use interface::Generator;
+use rule::Rule;
+
+/// How many rows each parallel task works on.
+const BAND_ROWS: usize = 32;
+
+#[derive(Debug, Default)]
+pub struct BitwiseGenerator {
+    rule: Rule,
+}
+
+impl BitwiseGenerator {
+    pub fn new() -> BitwiseGenerator {
+        BitwiseGenerator::with_rule(Rule::conway())
+    }
+
+    pub fn with_rule(rule: Rule) -> BitwiseGenerator {
+        BitwiseGenerator { rule }
+    }
+}
+
+impl Generator<BitBoard> for BitwiseGenerator {
+    fn generate(&self, board: &BitBoard) -> BitBoard {
+        let words = board.words_per_row();
+        let height = board.height().max(0) as usize;
+        let mut alive = vec![0; words * height];
+        let mut changed = vec![0; words * height];
+
+        if words > 0 {
+            let counts = Counts::for_rule(&self.rule);
+            let empty = vec![0; words];
+            alive
+                .par_chunks_mut(words * BAND_ROWS)
+                .zip(changed.par_chunks_mut(words * BAND_ROWS))
+                .enumerate()
+                .for_each(|(band, (alive, changed))| {
+                    let rows = alive.chunks_mut(words).zip(changed.chunks_mut(words));
+                    for (i, (alive, changed)) in rows.enumerate() {
+                        let y = (band * BAND_ROWS + i) as i32;
+                        let row = Row {
+                            above: row_at(board, y - 1, &empty),
+                            middle: row_at(board, y, &empty),
+                            below: row_at(board, y + 1, &empty),
+                        };
+                        row.next(board, &counts, alive, changed);
+                    }
+                });
+        }
+
+        BitBoard::with_words(board.width(), board.height(), alive, changed)
+            .with_topology(board.topology())
+    }
+}
+
+/// The packed row `y`, wrapping around a torus. Rows off the edge of a
+/// bounded board are `empty`.
+fn row_at<'a>(board: &'a BitBoard, y: i32, empty: &'a [u64]) -> &'a [u64] {
+    let y = match board.topology() {
+        Topology::Toroidal => y.rem_euclid(board.height()),
+        Topology::Bounded => y,
+    };
+    if y < 0 || y >= board.height() {
+        empty
+    } else {
+        board.alive_words(y as usize)
+    }
+}
+
+/// For each neighbour count, whether a dead cell is born and whether a
+/// living cell survives.
+struct Counts {
+    born: [bool; 9],
+    survives: [bool; 9],
+}
+
+impl Counts {
+    fn for_rule(rule: &Rule) -> Counts {
+        let mut counts = Counts {
+            born: [false; 9],
+            survives: [false; 9],
+        };
+        for n in 0..9 {
+            counts.born[n] = rule.is_born(n);
+            counts.survives[n] = rule.survives(n);
+        }
+        counts
+    }
+}
+
+/// A row along with the rows above and below it.
+struct Row<'a> {
+    above: &'a [u64],
+    middle: &'a [u64],
+    below: &'a [u64],
+}
+
+impl<'a> Row<'a> {
+    fn next(&self, board: &BitBoard, counts: &Counts, alive: &mut [u64], changed: &mut [u64]) {
+        let width = board.width() as usize;
+        let wraps = board.topology() == Topology::Toroidal;
+
+        for i in 0..alive.len() {
+            let (b0, b1, b2, b3) = sum(&[
+                west(self.above, i, width, wraps),
+                self.above[i],
+                east(self.above, i, width, wraps),
+                west(self.middle, i, width, wraps),
+                east(self.middle, i, width, wraps),
+                west(self.below, i, width, wraps),
+                self.below[i],
+                east(self.below, i, width, wraps),
+            ]);
+
+            let mut born = 0;
+            let mut survives = 0;
+            for n in 0..9 {
+                if !counts.born[n] && !counts.survives[n] {
+                    continue;
+                }
+                let matches = bit_matches(b0, n & 1) & bit_matches(b1, n & 2)
+                    & bit_matches(b2, n & 4) & bit_matches(b3, n & 8);
+                if counts.born[n] {
+                    born |= matches;
+                }
+                if counts.survives[n] {
+                    survives |= matches;
+                }
+            }
+
+            let current = self.middle[i];
+            alive[i] = (!current & born) | (current & survives);
+            changed[i] = alive[i] ^ current;
+        }
+    }
+}
+
+/// The bits that are set where the count has the bit `place` set, or clear
+/// where it doesn't.
+fn bit_matches(bits: u64, place: usize) -> u64 {
+    if place == 0 {
+        !bits
+    } else {
+        bits
+    }
+}
+
+/// Each cell's western neighbour, lined up with the cell: bit `x` holds
+/// column `x - 1`.
+fn west(row: &[u64], i: usize, width: usize, wraps: bool) -> u64 {
+    let carry = if i > 0 {
+        row[i - 1] >> 63
+    } else if wraps {
+        let last = width - 1;
+        (row[last / 64] >> (last % 64)) & 1
+    } else {
+        0
+    };
+    (row[i] << 1) | carry
+}
+
+/// Each cell's eastern neighbour, lined up with the cell: bit `x` holds
+/// column `x + 1`.
+fn east(row: &[u64], i: usize, width: usize, wraps: bool) -> u64 {
+    let shifted = row[i] >> 1;
+    if i + 1 < row.len() {
+        shifted | (row[i + 1] << 63)
+    } else if wraps {
+        shifted | ((row[0] & 1) << ((width - 1) % 64))
+    } else {
+        shifted
+    }
+}
+
+/// Adds three one bit numbers in each bit position, giving the sum and carry.
+fn full_add(a: u64, b: u64, c: u64) -> (u64, u64) {
+    let partial = a ^ b;
+    (partial ^ c, (a & b) | (partial & c))
+}
+
+/// Adds eight one bit numbers in each bit position, giving the four bits of
+/// the count from least to most significant.
+fn sum(neighbours: &[u64; 8]) -> (u64, u64, u64, u64) {
+    let &[a, b, c, d, e, f, g, h] = neighbours;
+    let (ones_a, twos_a) = full_add(a, b, c);
+    let (ones_b, twos_b) = full_add(d, e, f);
+    let (ones_c, twos_c) = full_add(g, h, 0);
+    let (ones, twos_d) = full_add(ones_a, ones_b, ones_c);
+    let (twos_e, fours_a) = full_add(twos_a, twos_b, twos_c);
+    let (twos, fours_b) = full_add(twos_e, twos_d, 0);
+    (ones, twos, fours_a ^ fours_b, fours_a & fours_b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use board::grid::GridBoard;
+    use generator::SimpleGenerator;
+    use interface::Board;
+
+    fn assert_matches_simple(width: i32, height: i32, topology: Topology, rule: Rule) {
+        let mut grid = GridBoard::seeded_random(width, height, 5, 0.4).with_topology(topology);
+        let mut bits = BitBoard::seeded_random(width, height, 5, 0.4).with_topology(topology);
+        let simple = SimpleGenerator::with_rule(rule);
+        let bitwise = BitwiseGenerator::with_rule(rule);
+
+        for generation in 0..20 {
+            grid = simple.generate(&grid);
+            bits = bitwise.generate(&bits);
+            assert_eq!(
+                bits.rows(),
+                grid.rows(),
+                "{}x{} {:?} board differs after {} generations",
+                width,
+                height,
+                topology,
+                generation + 1
+            );
+        }
+    }
+
+    #[test]
+    fn counts_every_number_of_neighbours() {
+        for n in 0..256u64 {
+            let neighbours = [
+                n & 1,
+                (n >> 1) & 1,
+                (n >> 2) & 1,
+                (n >> 3) & 1,
+                (n >> 4) & 1,
+                (n >> 5) & 1,
+                (n >> 6) & 1,
+                (n >> 7) & 1,
+            ];
+            let (b0, b1, b2, b3) = sum(&neighbours);
+
+            assert_eq!(b0 | (b1 << 1) | (b2 << 2) | (b3 << 3), n.count_ones() as u64);
+        }
+    }
+
+    #[test]
+    fn matches_the_simple_generator_on_a_bounded_board() {
+        for &width in &[1, 5, 63, 64, 65, 130] {
+            assert_matches_simple(width, 9, Topology::Bounded, Rule::conway());
+        }
+    }
+
+    #[test]
+    fn matches_the_simple_generator_on_a_torus() {
+        for &width in &[3, 5, 63, 64, 65, 130] {
+            assert_matches_simple(width, 9, Topology::Toroidal, Rule::conway());
+        }
+    }
+
+    #[test]
+    fn matches_the_simple_generator_across_bands() {
+        assert_matches_simple(70, BAND_ROWS as i32 * 2 + 3, Topology::Toroidal, Rule::conway());
+    }
+
+    #[test]
+    fn follows_other_rules() {
+        let high_life = "B36/S23".parse().unwrap();
+        let seeds = "B2/S".parse().unwrap();
+
+        assert_matches_simple(70, 12, Topology::Toroidal, high_life);
+        assert_matches_simple(70, 12, Topology::Bounded, seeds);
+    }
+
+    #[test]
+    fn an_empty_board_stays_empty() {
+        let board = BitBoard::new(0, 0);
+
+        assert_eq!(BitwiseGenerator::new().generate(&board), board);
+    }
+}
@@ -0,0 +1,630 @@
+use std::cell::RefCell;
+use std::cmp;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+
+use board::sparse::SparseBoard;
+use interface::{Board, Generator};
+use models::Coordinates;
+use rule::Rule;
+
+type NodeId = usize;
+
+const DEAD: NodeId = 0;
+const ALIVE: NodeId = 1;
+
+/// The largest power of two `HashLifeGenerator::step_pow2` can advance by
+/// while keeping the arithmetic within an `i64`.
+pub const MAX_STEP_EXPONENT: u32 = 60;
+
+/// Generates by building a canonicalised quadtree of the board and memoising
+/// the future of every node, so patterns with a lot of repetition in space
+/// or time can be advanced by enormous numbers of generations at once.
+///
+/// Works on the unbounded `SparseBoard`. Rules with B0 would fill the empty
+/// plane and are rejected.
+///
+/// A `SparseBoard` only holds cells whose coordinates fit in an `i32`.
+/// `step_pow2` and `advance` report cells that move past them with
+/// `HashLifeError::OutOfRange`, but as a `Generator` there is no way to
+/// report anything, so `generate` drops those cells, as though they had
+/// fallen off the edge of a very large bounded board.
+pub struct HashLifeGenerator {
+    max_nodes: usize,
+    universe: RefCell<Universe>,
+}
+
+impl HashLifeGenerator {
+    pub fn new() -> HashLifeGenerator {
+        HashLifeGenerator::for_rule(Rule::conway())
+    }
+
+    /// Fails with `HashLifeError::BirthOnZero` for rules with B0.
+    pub fn with_rule(rule: Rule) -> Result<HashLifeGenerator, HashLifeError> {
+        if rule.is_born(0) {
+            return Err(HashLifeError::BirthOnZero(rule));
+        }
+        Ok(HashLifeGenerator::for_rule(rule))
+    }
+
+    fn for_rule(rule: Rule) -> HashLifeGenerator {
+        HashLifeGenerator {
+            max_nodes: 1 << 22,
+            universe: RefCell::new(Universe::new(rule)),
+        }
+    }
+
+    /// Limits how many nodes are cached between steps. When a step leaves
+    /// more than `max_nodes` behind, everything not reachable from its result
+    /// is collected.
+    pub fn with_max_nodes(self, max_nodes: usize) -> HashLifeGenerator {
+        HashLifeGenerator { max_nodes, ..self }
+    }
+
+    pub fn cached_nodes(&self) -> usize {
+        self.universe.borrow().nodes.len()
+    }
+
+    /// Advances the board by 2^`exponent` generations in a single step, where
+    /// `exponent` is at most `MAX_STEP_EXPONENT`.
+    pub fn step_pow2(
+        &self,
+        board: &SparseBoard,
+        exponent: u32,
+    ) -> Result<SparseBoard, HashLifeError> {
+        if exponent > MAX_STEP_EXPONENT {
+            return Err(HashLifeError::TooManyGenerations);
+        }
+        let live_cells =
+            within_range(self.step_live_cells(board.live_cells().collect(), exponent))?;
+        Ok(board.succeeded_by(live_cells))
+    }
+
+    /// Advances the board by any number of generations below
+    /// 2^(`MAX_STEP_EXPONENT` + 1), one power of two at a time.
+    pub fn advance(
+        &self,
+        board: &SparseBoard,
+        generations: u64,
+    ) -> Result<SparseBoard, HashLifeError> {
+        if generations >> (MAX_STEP_EXPONENT + 1) != 0 {
+            return Err(HashLifeError::TooManyGenerations);
+        }
+        let mut live_cells = board.live_cells().collect();
+        for exponent in 0..MAX_STEP_EXPONENT + 1 {
+            if generations & (1 << exponent) != 0 {
+                live_cells = within_range(self.step_live_cells(live_cells, exponent))?;
+            }
+        }
+        Ok(board.succeeded_by(live_cells))
+    }
+
+    /// Steps the living cells, returning those that still fit in a board's
+    /// coordinates along with how many moved past them.
+    fn step_live_cells(
+        &self,
+        live_cells: Vec<Coordinates>,
+        exponent: u32,
+    ) -> (Vec<Coordinates>, usize) {
+        let mut universe = self.universe.borrow_mut();
+        let level = level_for(&live_cells, exponent);
+        let half = 1i64 << (level - 1);
+        let points: Vec<(i64, i64)> = live_cells
+            .into_iter()
+            .map(|c| (i64::from(c.x) + half, i64::from(c.y) + half))
+            .collect();
+
+        let root = universe.build(level, 0, 0, points);
+        let result = universe.successor(root, exponent as u8);
+
+        let mut points = Vec::new();
+        universe.collect(result, -half / 2, -half / 2, &mut points);
+
+        if universe.nodes.len() > self.max_nodes {
+            universe.collect_garbage(&[result]);
+        }
+        let found = points.len();
+        let cells: Vec<Coordinates> = points
+            .into_iter()
+            .filter_map(|(x, y)| {
+                Some(Coordinates {
+                    x: i32::try_from(x).ok()?,
+                    y: i32::try_from(y).ok()?,
+                })
+            })
+            .collect();
+        let lost = found - cells.len();
+        (cells, lost)
+    }
+}
+
+fn within_range(
+    (cells, lost): (Vec<Coordinates>, usize),
+) -> Result<Vec<Coordinates>, HashLifeError> {
+    if lost > 0 {
+        return Err(HashLifeError::OutOfRange);
+    }
+    Ok(cells)
+}
+
+impl Default for HashLifeGenerator {
+    fn default() -> HashLifeGenerator {
+        HashLifeGenerator::new()
+    }
+}
+
+impl Generator<SparseBoard> for HashLifeGenerator {
+    /// Cells that move past the coordinates a `SparseBoard` can hold are
+    /// dropped.
+    fn generate(&self, board: &SparseBoard) -> SparseBoard {
+        let (live_cells, _) = self.step_live_cells(board.live_cells().collect(), 0);
+        board.succeeded_by(live_cells)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum HashLifeError {
+    /// The rule has B0, so the empty plane would fill with living cells.
+    BirthOnZero(Rule),
+    /// More generations were asked for than can be stepped at once.
+    TooManyGenerations,
+    /// Cells moved past the largest coordinates a `SparseBoard` can hold.
+    OutOfRange,
+}
+
+impl fmt::Display for HashLifeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            HashLifeError::BirthOnZero(rule) => write!(
+                f,
+                "HashLife can't run {}: rules with B0 fill the empty plane",
+                rule
+            ),
+            HashLifeError::TooManyGenerations => write!(
+                f,
+                "can advance fewer than 2^{} generations at once",
+                MAX_STEP_EXPONENT + 1
+            ),
+            HashLifeError::OutOfRange => {
+                f.write_str("cells moved past the largest coordinates a board can hold")
+            }
+        }
+    }
+}
+
+impl Error for HashLifeError {}
+
+/// Finds a level whose central quarter contains the pattern with room for it
+/// to grow at the speed of light for 2^`exponent` generations.
+fn level_for(live_cells: &[Coordinates], exponent: u32) -> u8 {
+    let reach = live_cells
+        .iter()
+        .map(|c| {
+            cmp::max(
+                cmp::max(-i64::from(c.x), i64::from(c.x) + 1),
+                cmp::max(-i64::from(c.y), i64::from(c.y) + 1),
+            )
+        })
+        .max()
+        .unwrap_or(0)
+        + (1 << exponent);
+
+    let mut level = cmp::max(exponent + 2, 3);
+    while 1i64 << (level - 2) < reach {
+        level += 1;
+    }
+    level as u8
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Node {
+    level: u8,
+    children: [NodeId; 4],
+    population: u64,
+}
+
+struct Universe {
+    rule: Rule,
+    nodes: Vec<Node>,
+    index: HashMap<[NodeId; 4], NodeId>,
+    results: HashMap<(NodeId, u8), NodeId>,
+    empties: Vec<NodeId>,
+}
+
+impl Universe {
+    fn new(rule: Rule) -> Universe {
+        let leaf = |population| Node {
+            level: 0,
+            children: [DEAD; 4],
+            population,
+        };
+        Universe {
+            rule,
+            nodes: vec![leaf(0), leaf(1)],
+            index: HashMap::new(),
+            results: HashMap::new(),
+            empties: vec![DEAD],
+        }
+    }
+
+    fn join(&mut self, nw: NodeId, ne: NodeId, sw: NodeId, se: NodeId) -> NodeId {
+        let children = [nw, ne, sw, se];
+        if let Some(&id) = self.index.get(&children) {
+            return id;
+        }
+
+        let node = Node {
+            level: self.nodes[nw].level + 1,
+            children,
+            population: children.iter().map(|&c| self.nodes[c].population).sum(),
+        };
+        let id = self.nodes.len();
+        self.nodes.push(node);
+        self.index.insert(children, id);
+        id
+    }
+
+    fn empty(&mut self, level: u8) -> NodeId {
+        while self.empties.len() <= level as usize {
+            let below = *self.empties.last().unwrap();
+            let empty = self.join(below, below, below, below);
+            self.empties.push(empty);
+        }
+        self.empties[level as usize]
+    }
+
+    fn build(&mut self, level: u8, x: i64, y: i64, points: Vec<(i64, i64)>) -> NodeId {
+        if points.is_empty() {
+            return self.empty(level);
+        }
+        if level == 0 {
+            return ALIVE;
+        }
+
+        let half = 1i64 << (level - 1);
+        let mut quadrants = [Vec::new(), Vec::new(), Vec::new(), Vec::new()];
+        for (px, py) in points {
+            let quadrant = (px >= x + half) as usize + 2 * (py >= y + half) as usize;
+            quadrants[quadrant].push((px, py));
+        }
+        let [nw, ne, sw, se] = quadrants;
+
+        let nw = self.build(level - 1, x, y, nw);
+        let ne = self.build(level - 1, x + half, y, ne);
+        let sw = self.build(level - 1, x, y + half, sw);
+        let se = self.build(level - 1, x + half, y + half, se);
+        self.join(nw, ne, sw, se)
+    }
+
+    fn collect(&self, node: NodeId, x: i64, y: i64, points: &mut Vec<(i64, i64)>) {
+        let Node {
+            level,
+            children,
+            population,
+        } = self.nodes[node];
+        if population == 0 {
+            return;
+        }
+        if level == 0 {
+            points.push((x, y));
+            return;
+        }
+
+        let half = 1i64 << (level - 1);
+        self.collect(children[0], x, y, points);
+        self.collect(children[1], x + half, y, points);
+        self.collect(children[2], x, y + half, points);
+        self.collect(children[3], x + half, y + half, points);
+    }
+
+    /// The node's grandchildren laid out as a 4x4 grid, indexed by `[y][x]`.
+    fn grandchildren(&self, node: NodeId) -> [[NodeId; 4]; 4] {
+        let mut grid = [[DEAD; 4]; 4];
+        for (quadrant, &child) in self.nodes[node].children.iter().enumerate() {
+            for (i, &grandchild) in self.nodes[child].children.iter().enumerate() {
+                grid[(quadrant / 2) * 2 + i / 2][(quadrant % 2) * 2 + i % 2] = grandchild;
+            }
+        }
+        grid
+    }
+
+    fn centre(&mut self, node: NodeId) -> NodeId {
+        let g = self.grandchildren(node);
+        self.join(g[1][1], g[1][2], g[2][1], g[2][2])
+    }
+
+    /// The centre of `node`, 2^`step` generations into the future. `step` must
+    /// be at most two less than the node's level.
+    fn successor(&mut self, node: NodeId, step: u8) -> NodeId {
+        let Node {
+            level, population, ..
+        } = self.nodes[node];
+        if population == 0 {
+            return self.empty(level - 1);
+        }
+        if let Some(&result) = self.results.get(&(node, step)) {
+            return result;
+        }
+
+        let result = if level == 2 {
+            self.evolve_4x4(node)
+        } else {
+            self.evolve(node, step)
+        };
+        self.results.insert((node, step), result);
+        result
+    }
+
+    fn evolve(&mut self, node: NodeId, step: u8) -> NodeId {
+        let level = self.nodes[node].level;
+        let full_speed = step == level - 2;
+        let inner_step = if full_speed { level - 3 } else { step };
+
+        // Advance the nine overlapping subnodes, each half the size of `node`.
+        let g = self.grandchildren(node);
+        let mut c = [[DEAD; 3]; 3];
+        for y in 0..3 {
+            for x in 0..3 {
+                let subnode = self.join(g[y][x], g[y][x + 1], g[y + 1][x], g[y + 1][x + 1]);
+                c[y][x] = self.successor(subnode, inner_step);
+            }
+        }
+
+        // Then combine them into four quadrants and advance those again, or
+        // just take their centres when a smaller step was asked for.
+        let mut results = [DEAD; 4];
+        for (i, result) in results.iter_mut().enumerate() {
+            let (x, y) = (i % 2, i / 2);
+            let quadrant = self.join(c[y][x], c[y][x + 1], c[y + 1][x], c[y + 1][x + 1]);
+            *result = if full_speed {
+                self.successor(quadrant, inner_step)
+            } else {
+                self.centre(quadrant)
+            };
+        }
+        self.join(results[0], results[1], results[2], results[3])
+    }
+
+    /// Runs a single generation on a 4x4 node by brute force.
+    fn evolve_4x4(&mut self, node: NodeId) -> NodeId {
+        let grid = self.grandchildren(node);
+
+        let mut next = [DEAD; 4];
+        for (i, result) in next.iter_mut().enumerate() {
+            let (x, y) = (1 + i % 2, 1 + i / 2);
+            let neighbours = (y - 1..y + 2)
+                .flat_map(|ny| (x - 1..x + 2).map(move |nx| (nx, ny)))
+                .filter(|&(nx, ny)| (nx, ny) != (x, y) && grid[ny][nx] == ALIVE)
+                .count();
+            let alive = if grid[y][x] == ALIVE {
+                self.rule.survives(neighbours)
+            } else {
+                self.rule.is_born(neighbours)
+            };
+            *result = if alive { ALIVE } else { DEAD };
+        }
+        self.join(next[0], next[1], next[2], next[3])
+    }
+
+    /// Drops every node that isn't reachable from `roots`, along with any
+    /// memoised results that refer to them.
+    fn collect_garbage(&mut self, roots: &[NodeId]) {
+        let mut reachable = vec![false; self.nodes.len()];
+        reachable[DEAD] = true;
+        reachable[ALIVE] = true;
+        let mut pending: Vec<NodeId> = roots.to_vec();
+        while let Some(node) = pending.pop() {
+            if reachable[node] {
+                continue;
+            }
+            reachable[node] = true;
+            pending.extend_from_slice(&self.nodes[node].children);
+        }
+
+        let mut remapped = vec![None; self.nodes.len()];
+        let old_nodes = std::mem::take(&mut self.nodes);
+        self.index.clear();
+        self.empties.clear();
+        for (id, node) in old_nodes.into_iter().enumerate() {
+            if !reachable[id] {
+                continue;
+            }
+            let new_id = self.nodes.len();
+            remapped[id] = Some(new_id);
+            if node.level == 0 {
+                self.nodes.push(node);
+                continue;
+            }
+            let children = [
+                remapped[node.children[0]].unwrap(),
+                remapped[node.children[1]].unwrap(),
+                remapped[node.children[2]].unwrap(),
+                remapped[node.children[3]].unwrap(),
+            ];
+            self.nodes.push(Node { children, ..node });
+            self.index.insert(children, new_id);
+        }
+        self.empties.push(DEAD);
+
+        self.results = self
+            .results
+            .iter()
+            .filter_map(
+                |(&(node, step), &result)| match (remapped[node], remapped[result]) {
+                    (Some(node), Some(result)) => Some(((node, step), result)),
+                    _ => None,
+                },
+            )
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use generator::SimpleGenerator;
+
+    fn glider() -> SparseBoard {
+        SparseBoard::with_live_cells(vec![
+            Coordinates { x: 1, y: 0 },
+            Coordinates { x: 2, y: 1 },
+            Coordinates { x: 0, y: 2 },
+            Coordinates { x: 1, y: 2 },
+            Coordinates { x: 2, y: 2 },
+        ])
+    }
+
+    fn r_pentomino() -> SparseBoard {
+        SparseBoard::with_live_cells(vec![
+            Coordinates { x: 1, y: 0 },
+            Coordinates { x: 2, y: 0 },
+            Coordinates { x: 0, y: 1 },
+            Coordinates { x: 1, y: 1 },
+            Coordinates { x: 1, y: 2 },
+        ])
+    }
+
+    fn sorted_live_cells(board: &SparseBoard) -> Vec<Coordinates> {
+        let mut cells: Vec<Coordinates> = board.live_cells().collect();
+        cells.sort_by_key(|c| (c.y, c.x));
+        cells
+    }
+
+    fn translated(board: &SparseBoard, dx: i32, dy: i32) -> Vec<Coordinates> {
+        sorted_live_cells(&SparseBoard::with_live_cells(
+            board.live_cells().map(|c| Coordinates {
+                x: c.x + dx,
+                y: c.y + dy,
+            }),
+        ))
+    }
+
+    #[test]
+    fn a_single_generation_matches_the_simple_generator() {
+        let hashlife = HashLifeGenerator::new();
+        let simple = SimpleGenerator::new();
+        let board = r_pentomino();
+
+        assert_eq!(hashlife.generate(&board), simple.generate(&board));
+    }
+
+    #[test]
+    fn advancing_matches_running_the_simple_generator() {
+        let hashlife = HashLifeGenerator::new();
+        let simple = SimpleGenerator::new();
+        let mut board = r_pentomino();
+        for _ in 0..100 {
+            board = simple.generate(&board);
+        }
+
+        let result = hashlife.advance(&r_pentomino(), 100).unwrap();
+
+        assert_eq!(sorted_live_cells(&result), sorted_live_cells(&board));
+    }
+
+    #[test]
+    fn stepping_by_a_power_of_two_moves_a_glider_far_away() {
+        let hashlife = HashLifeGenerator::new();
+
+        let result = hashlife.step_pow2(&glider(), 20).unwrap();
+
+        assert_eq!(
+            sorted_live_cells(&result),
+            translated(&glider(), 1 << 18, 1 << 18)
+        );
+    }
+
+    #[test]
+    fn the_rule_is_respected() {
+        let seeds = HashLifeGenerator::with_rule(Rule::new(&[2], &[])).unwrap();
+        let domino = SparseBoard::with_live_cells(vec![
+            Coordinates { x: 0, y: 0 },
+            Coordinates { x: 1, y: 0 },
+        ]);
+
+        let result = seeds.generate(&domino);
+
+        let mut expected = vec![
+            Coordinates { x: 0, y: -1 },
+            Coordinates { x: 1, y: -1 },
+            Coordinates { x: 0, y: 1 },
+            Coordinates { x: 1, y: 1 },
+        ];
+        expected.sort_by_key(|c| (c.y, c.x));
+        assert_eq!(sorted_live_cells(&result), expected);
+    }
+
+    #[test]
+    fn rules_with_b0_are_rejected() {
+        let rule = Rule::new(&[0, 3], &[2, 3]);
+
+        let error = HashLifeGenerator::with_rule(rule).err();
+
+        assert_eq!(error, Some(HashLifeError::BirthOnZero(rule)));
+        assert!(error
+            .unwrap()
+            .to_string()
+            .ends_with("rules with B0 fill the empty plane"));
+    }
+
+    #[test]
+    fn steps_that_are_too_large_are_rejected() {
+        let hashlife = HashLifeGenerator::new();
+
+        assert_eq!(
+            hashlife.step_pow2(&glider(), MAX_STEP_EXPONENT + 1),
+            Err(HashLifeError::TooManyGenerations)
+        );
+        assert_eq!(
+            hashlife.advance(&glider(), u64::MAX),
+            Err(HashLifeError::TooManyGenerations)
+        );
+    }
+
+    fn glider_at_the_right_edge() -> SparseBoard {
+        SparseBoard::with_live_cells(glider().live_cells().map(|c| Coordinates {
+            x: c.x + (i32::MAX - 4),
+            y: c.y,
+        }))
+    }
+
+    #[test]
+    fn cells_that_leave_the_board_are_reported() {
+        let hashlife = HashLifeGenerator::new();
+
+        assert_eq!(
+            hashlife.step_pow2(&glider_at_the_right_edge(), 4),
+            Err(HashLifeError::OutOfRange)
+        );
+    }
+
+    #[test]
+    fn generating_drops_cells_that_leave_the_board() {
+        let hashlife = HashLifeGenerator::new();
+        let mut board = glider_at_the_right_edge();
+        for _ in 0..8 {
+            board = hashlife.generate(&board);
+        }
+        assert_eq!(board.population(), 5);
+
+        for _ in 0..100 {
+            board = hashlife.generate(&board);
+        }
+
+        assert!(board.population() < 5);
+    }
+
+    #[test]
+    fn garbage_collection_keeps_the_cache_bounded() {
+        let hashlife = HashLifeGenerator::new().with_max_nodes(64);
+        let unbounded = HashLifeGenerator::new();
+
+        let collected = hashlife.advance(&r_pentomino(), 1000).unwrap();
+        let expected = unbounded.advance(&r_pentomino(), 1000).unwrap();
+
+        assert_eq!(sorted_live_cells(&collected), sorted_live_cells(&expected));
+        assert!(hashlife.cached_nodes() < unbounded.cached_nodes());
+    }
+}
@@ -0,0 +1,55 @@
+use rayon::prelude::*;
+use models::{Cell, CellState};
+use interface::{Board, Generator};
+use engine::process;
+use rule::Rule;
+
+pub mod active;
+pub mod bitwise;
+pub mod hashlife;
+
+pub use self::active::ActiveRegionGenerator;
+pub use self::bitwise::BitwiseGenerator;
+pub use self::hashlife::HashLifeGenerator;
+
+#[derive(Debug, Default)]
+pub struct SimpleGenerator {
+    rule: Rule,
+}
+
+impl SimpleGenerator {
+    pub fn new() -> SimpleGenerator {
+        SimpleGenerator::with_rule(Rule::conway())
+    }
+
+    pub fn with_rule(rule: Rule) -> SimpleGenerator {
+        SimpleGenerator { rule }
+    }
+}
+
+impl<T: Board> Generator<T> for SimpleGenerator {
+    fn generate(&self, board: &T) -> T {
+        let new_rows = board
+            .rows()
+            .par_iter()
+            .map(|row| next_row(&self.rule, board, row))
+            .collect();
+
+        board.successor(new_rows)
+    }
+}
+
+fn next_row<T: Board>(rule: &Rule, board: &T, row: &[Cell]) -> Vec<Cell> {
+    row.par_iter()
+        .map(|cell| process(rule, cell, neighbour_states(board, cell)))
+        .collect()
+}
+
+fn neighbour_states<T: Board>(board: &T, cell: &Cell) -> Vec<CellState> {
+    cell.location
+        .neighbours()
+        .into_iter()
+        .filter_map(|n| board.at(n))
+        .map(|x| x.cell_state)
+        .collect()
+}
@@ -0,0 +1,234 @@
+//! Past generations of a game, so that it can be rewound.
+
+use std::collections::VecDeque;
+use std::mem;
+
+use interface::Board;
+use models::{CellState, Coordinates};
+
+const DEFAULT_BUDGET: usize = 64 * 1024 * 1024;
+
+/// The boards a game has been through, for stepping backwards.
+///
+/// Only the latest board is kept in full. Each generation before it is kept
+/// as the cells whose state differs from the generation after, so rewinding
+/// flips those cells back. Once the differences take up more memory than the
+/// budget allows, the oldest are forgotten.
+///
+/// Rewound boards only have living and dead cells, without any that were
+/// just born or just died.
+#[derive(Debug, Clone)]
+pub struct History<T: Board> {
+    current: T,
+    generation: u32,
+    steps: VecDeque<Vec<Coordinates>>,
+    budget: usize,
+    used: usize,
+}
+
+impl<T: Board> History<T> {
+    pub fn new(board: T) -> History<T> {
+        History {
+            current: board,
+            generation: 0,
+            steps: VecDeque::new(),
+            budget: DEFAULT_BUDGET,
+            used: 0,
+        }
+    }
+
+    /// Changes how many bytes the history may use, not counting the latest
+    /// board, forgetting the oldest generations if it's already using more.
+    pub fn with_budget(mut self, budget: usize) -> History<T> {
+        self.budget = budget;
+        self.trim();
+        self
+    }
+
+    /// The latest board.
+    pub fn current(&self) -> &T {
+        &self.current
+    }
+
+    /// How many generations came before the latest board, including any
+    /// that have been forgotten.
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    /// How many generations the history can be rewound by.
+    pub fn depth(&self) -> u32 {
+        self.steps.len() as u32
+    }
+
+    /// Roughly how many bytes the history is using, not counting the latest
+    /// board.
+    pub fn memory_used(&self) -> usize {
+        self.used
+    }
+
+    /// Records the next generation. Any board can follow the last one, such
+    /// as one that was drawn on before it was generated from.
+    pub fn push(&mut self, board: T) {
+        let mut changed: Vec<Coordinates> = board
+            .live_cells()
+            .filter(|&c| !is_alive(&self.current, c))
+            .collect();
+        changed.extend(self.current.live_cells().filter(|&c| !is_alive(&board, c)));
+
+        self.used += size_of_step(&changed);
+        self.steps.push_back(changed);
+        self.current = board;
+        self.generation += 1;
+        self.trim();
+    }
+
+    /// Goes back up to `generations` generations, as far as the history
+    /// reaches, and returns how many it went back. Pushing a board after
+    /// rewinding replaces the generations that were rewound.
+    pub fn rewind(&mut self, generations: u32) -> u32 {
+        let mut rewound = 0;
+        while rewound < generations {
+            let changed = match self.steps.pop_back() {
+                Some(changed) => changed,
+                None => break,
+            };
+            self.used -= size_of_step(&changed);
+            for c in changed {
+                let cell_state = if is_alive(&self.current, c) {
+                    CellState::Dead
+                } else {
+                    CellState::Alive
+                };
+                self.current.set(c, cell_state);
+            }
+            self.generation -= 1;
+            rewound += 1;
+        }
+        rewound
+    }
+
+    /// A copy of the history rewound by `generations` generations, to
+    /// explore another future from there while keeping this one.
+    pub fn branch(&self, generations: u32) -> History<T> {
+        let mut branch = self.clone();
+        branch.rewind(generations);
+        branch
+    }
+
+    /// Forgets the oldest generations until the history fits its budget.
+    fn trim(&mut self) {
+        while self.used > self.budget {
+            match self.steps.pop_front() {
+                Some(changed) => self.used -= size_of_step(&changed),
+                None => break,
+            }
+        }
+    }
+}
+
+fn is_alive<T: Board>(board: &T, coordinates: Coordinates) -> bool {
+    board
+        .at(coordinates)
+        .is_some_and(|cell| cell.cell_state.is_alive())
+}
+
+fn size_of_step(changed: &[Coordinates]) -> usize {
+    mem::size_of::<Vec<Coordinates>>() + mem::size_of_val(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use board::grid::GridBoard;
+    use board::sparse::SparseBoard;
+    use generator::SimpleGenerator;
+    use interface::Generator;
+
+    fn played<T: Board>(board: T, generations: u32) -> (History<T>, Vec<Vec<Coordinates>>) {
+        let generator = SimpleGenerator::new();
+        let mut history = History::new(board.clone());
+        let mut seen = vec![live_cells(&board)];
+        let mut board = board;
+        for _ in 0..generations {
+            board = generator.generate(&board);
+            seen.push(live_cells(&board));
+            history.push(board.clone());
+        }
+        (history, seen)
+    }
+
+    fn live_cells<T: Board>(board: &T) -> Vec<Coordinates> {
+        let mut cells: Vec<Coordinates> = board.live_cells().collect();
+        cells.sort_by_key(|c| (c.y, c.x));
+        cells
+    }
+
+    #[test]
+    fn rewinds_to_earlier_generations() {
+        let (mut history, seen) = played(GridBoard::seeded_random(20, 20, 1, 0.4), 10);
+
+        assert_eq!(history.generation(), 10);
+        assert_eq!(history.rewind(1), 1);
+        assert_eq!(live_cells(history.current()), seen[9]);
+        assert_eq!(history.rewind(4), 4);
+        assert_eq!(history.generation(), 5);
+        assert_eq!(live_cells(history.current()), seen[5]);
+        assert_eq!(history.rewind(100), 5);
+        assert_eq!(live_cells(history.current()), seen[0]);
+    }
+
+    #[test]
+    fn rewinds_cells_that_died_out_of_sight_on_an_unbounded_board() {
+        let lonely = SparseBoard::with_live_cells(vec![Coordinates { x: 5, y: 5 }]);
+        let (mut history, _) = played(lonely, 1);
+
+        assert_eq!(history.current().population(), 0);
+        history.rewind(1);
+        assert_eq!(
+            live_cells(history.current()),
+            vec![Coordinates { x: 5, y: 5 }]
+        );
+    }
+
+    #[test]
+    fn pushing_after_rewinding_starts_a_new_future() {
+        let (mut history, seen) = played(GridBoard::seeded_random(10, 10, 2, 0.5), 4);
+        history.rewind(2);
+
+        let empty = GridBoard::fill_with(10, 10, Box::new(|_| CellState::Dead));
+        history.push(empty);
+
+        assert_eq!(history.generation(), 3);
+        assert_eq!(history.depth(), 3);
+        history.rewind(1);
+        assert_eq!(live_cells(history.current()), seen[2]);
+    }
+
+    #[test]
+    fn branches_leave_the_original_alone() {
+        let (history, seen) = played(GridBoard::seeded_random(10, 10, 3, 0.5), 6);
+
+        let branch = history.branch(3);
+
+        assert_eq!(branch.generation(), 3);
+        assert_eq!(live_cells(branch.current()), seen[3]);
+        assert_eq!(history.generation(), 6);
+        assert_eq!(live_cells(history.current()), seen[6]);
+    }
+
+    #[test]
+    fn the_oldest_generations_are_forgotten_to_stay_within_budget() {
+        let (history, seen) = played(GridBoard::seeded_random(20, 20, 4, 0.5), 10);
+        let budget = history.memory_used() / 2;
+
+        let mut history = history.with_budget(budget);
+
+        assert!(history.memory_used() <= budget);
+        assert!(history.depth() < 10);
+        let depth = history.depth();
+        assert_eq!(history.rewind(10), depth);
+        assert_eq!(live_cells(history.current()), seen[(10 - depth) as usize]);
+        assert_eq!(history.memory_used(), 0);
+    }
+}
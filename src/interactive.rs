@@ -0,0 +1,380 @@
+use std::io::{self, stdout, Write};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use termion::event::{Event, Key, MouseButton, MouseEvent};
+use termion::input::{MouseTerminal, TermRead};
+use termion::raw::IntoRawMode;
+use termion::{async_stdin, clear, cursor};
+
+use history::History;
+use interface::{Board, CellState, Coordinates, Generator, Renderer};
+
+const MIN_DELAY: Duration = Duration::from_millis(1);
+const MAX_DELAY: Duration = Duration::from_secs(2);
+const POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Action {
+    TogglePause,
+    Step,
+    Back,
+    Faster,
+    Slower,
+    Reseed,
+    Quit,
+}
+
+impl Action {
+    pub fn for_key(key: Key) -> Option<Action> {
+        match key {
+            Key::Char(' ') => Some(Action::TogglePause),
+            Key::Char('n') => Some(Action::Step),
+            Key::Char('b') => Some(Action::Back),
+            Key::Char('+') | Key::Char('=') => Some(Action::Faster),
+            Key::Char('-') | Key::Char('_') => Some(Action::Slower),
+            Key::Char('r') => Some(Action::Reseed),
+            Key::Char('q') | Key::Esc | Key::Ctrl('c') => Some(Action::Quit),
+            _ => None,
+        }
+    }
+}
+
+/// The playback state that the keyboard controls.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Controls {
+    pub paused: bool,
+    pub delay: Duration,
+}
+
+impl Controls {
+    pub fn new(delay: Duration) -> Controls {
+        Controls {
+            paused: false,
+            delay,
+        }
+    }
+
+    /// Updates the playback state. Stepping either way pauses the game, so
+    /// that the generation it shows stays on screen.
+    pub fn apply(&mut self, action: Action) {
+        match action {
+            Action::TogglePause => self.paused = !self.paused,
+            Action::Step | Action::Back => self.paused = true,
+            Action::Faster => {
+                self.delay /= 2;
+                if self.delay < MIN_DELAY {
+                    self.delay = Duration::from_millis(0);
+                }
+            }
+            Action::Slower => {
+                self.delay = if self.delay < MIN_DELAY {
+                    MIN_DELAY
+                } else {
+                    (self.delay * 2).min(MAX_DELAY)
+                }
+            }
+            Action::Reseed | Action::Quit => {}
+        }
+    }
+}
+
+/// Draws on a board with the mouse. Pressing the button toggles the cell
+/// under it, and dragging paints the cells it passes over the same way.
+#[derive(Debug, Default)]
+pub struct Editor {
+    painting: Option<CellState>,
+}
+
+impl Editor {
+    pub fn new() -> Editor {
+        Editor { painting: None }
+    }
+
+    pub fn press<T: Board>(&mut self, board: &mut T, coordinates: Coordinates) {
+        let cell_state = match board.at(coordinates).map(|c| c.cell_state) {
+            Some(CellState::Alive) | Some(CellState::Born) => CellState::Dead,
+            Some(_) => CellState::Alive,
+            None => return,
+        };
+        board.set(coordinates, cell_state);
+        self.painting = Some(cell_state);
+    }
+
+    pub fn drag<T: Board>(&mut self, board: &mut T, coordinates: Coordinates) {
+        if let Some(cell_state) = self.painting {
+            board.set(coordinates, cell_state);
+        }
+    }
+
+    pub fn release(&mut self) {
+        self.painting = None;
+    }
+}
+
+/// Plays the game in a raw mode terminal, taking commands from the keyboard:
+/// space pauses and resumes, `n` steps a single generation, `b` steps back
+/// one, `+` and `-` change the speed, `r` starts again from a new board and
+/// `q` quits. While paused, cells can be drawn with the mouse.
+///
+/// Stepping back goes to the generation as it was played, without anything
+/// drawn on it since.
+pub struct Interactive<'a, T, U, V>
+where
+    T: Board,
+    U: 'a + Renderer<T>,
+    V: 'a + Generator<T>,
+{
+    board: T,
+    renderer: &'a U,
+    generator: &'a V,
+    controls: Controls,
+    history_budget: Option<usize>,
+}
+
+impl<'a, T, U, V> Interactive<'a, T, U, V>
+where
+    T: Board,
+    U: 'a + Renderer<T>,
+    V: 'a + Generator<T>,
+{
+    pub fn new(
+        board: T,
+        renderer: &'a U,
+        generator: &'a V,
+        delay: Duration,
+    ) -> Interactive<'a, T, U, V> {
+        Interactive {
+            board,
+            renderer,
+            generator,
+            controls: Controls::new(delay),
+            history_budget: None,
+        }
+    }
+
+    /// Changes how many bytes the generations that can be stepped back to
+    /// may use.
+    pub fn with_history_budget(self, budget: usize) -> Interactive<'a, T, U, V> {
+        Interactive {
+            history_budget: Some(budget),
+            ..self
+        }
+    }
+
+    /// Runs until the player quits, returning the board on screen at the
+    /// time. `reseed` provides the board to start again from.
+    pub fn play<F>(self, mut reseed: F) -> io::Result<T>
+    where
+        F: FnMut() -> T,
+    {
+        let _screen = Screen(MouseTerminal::from(stdout().into_raw_mode()?));
+        let mut events = async_stdin().events();
+        let mut controls = self.controls;
+        let mut editor = Editor::new();
+        let mut board = self.board;
+        let budget = self.history_budget;
+        let new_history = |board: &T| {
+            let history = History::new(board.clone());
+            match budget {
+                Some(budget) => history.with_budget(budget),
+                None => history,
+            }
+        };
+        let mut history = new_history(&board);
+
+        self.renderer.render(&board);
+        let mut last_generation = Instant::now();
+        'play: loop {
+            let mut step = false;
+            for event in &mut events {
+                let action = match event? {
+                    Event::Key(key) => Action::for_key(key),
+                    Event::Mouse(mouse) if controls.paused => {
+                        edit(self.renderer, &mut editor, &mut board, mouse);
+                        None
+                    }
+                    _ => None,
+                };
+                match action {
+                    Some(Action::Quit) => break 'play,
+                    Some(Action::Reseed) => {
+                        board = reseed();
+                        history = new_history(&board);
+                        self.renderer.render(&board);
+                        last_generation = Instant::now();
+                    }
+                    Some(Action::Back) => {
+                        controls.apply(Action::Back);
+                        history.rewind(1);
+                        board = history.current().clone();
+                        self.renderer.render(&board);
+                    }
+                    Some(action) => {
+                        step |= action == Action::Step;
+                        controls.apply(action);
+                    }
+                    None => {}
+                }
+            }
+
+            if step || (!controls.paused && last_generation.elapsed() >= controls.delay) {
+                board = self.generator.generate(&board);
+                history.push(board.clone());
+                self.renderer.render(&board);
+                last_generation = Instant::now();
+            } else {
+                thread::sleep(POLL_INTERVAL);
+            }
+        }
+
+        Ok(board)
+    }
+}
+
+/// The terminal while a game is being played, which is cleared and gets its
+/// cursor back however the game ends.
+struct Screen<W: Write>(W);
+
+impl<W: Write> Drop for Screen<W> {
+    fn drop(&mut self) {
+        let _ = write!(
+            self.0,
+            "{}{}{}",
+            clear::All,
+            cursor::Goto(1, 1),
+            cursor::Show
+        );
+        let _ = self.0.flush();
+    }
+}
+
+/// Applies a mouse event to the board, redrawing it if anything happened.
+fn edit<T: Board, U: Renderer<T>>(
+    renderer: &U,
+    editor: &mut Editor,
+    board: &mut T,
+    mouse: MouseEvent,
+) {
+    match mouse {
+        MouseEvent::Press(MouseButton::Left, column, line) => {
+            if let Some(coordinates) = renderer.coordinates_at(board, column, line) {
+                editor.press(board, coordinates);
+            }
+        }
+        MouseEvent::Hold(column, line) => {
+            if let Some(coordinates) = renderer.coordinates_at(board, column, line) {
+                editor.drag(board, coordinates);
+            }
+        }
+        MouseEvent::Release(..) => editor.release(),
+        _ => return,
+    }
+    renderer.render(board);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use board::grid::GridBoard;
+
+    fn state_at(board: &GridBoard, x: i32, y: i32) -> Option<CellState> {
+        board.at(Coordinates { x, y }).map(|c| c.cell_state)
+    }
+
+    #[test]
+    fn keys_map_to_actions() {
+        assert_eq!(Action::for_key(Key::Char(' ')), Some(Action::TogglePause));
+        assert_eq!(Action::for_key(Key::Char('n')), Some(Action::Step));
+        assert_eq!(Action::for_key(Key::Char('b')), Some(Action::Back));
+        assert_eq!(Action::for_key(Key::Char('+')), Some(Action::Faster));
+        assert_eq!(Action::for_key(Key::Char('-')), Some(Action::Slower));
+        assert_eq!(Action::for_key(Key::Char('r')), Some(Action::Reseed));
+        assert_eq!(Action::for_key(Key::Char('q')), Some(Action::Quit));
+        assert_eq!(Action::for_key(Key::Ctrl('c')), Some(Action::Quit));
+        assert_eq!(Action::for_key(Key::Char('x')), None);
+    }
+
+    #[test]
+    fn space_pauses_and_resumes() {
+        let mut controls = Controls::new(Duration::from_millis(30));
+
+        controls.apply(Action::TogglePause);
+        assert!(controls.paused);
+        controls.apply(Action::TogglePause);
+        assert!(!controls.paused);
+    }
+
+    #[test]
+    fn stepping_pauses() {
+        let mut controls = Controls::new(Duration::from_millis(30));
+
+        controls.apply(Action::Step);
+        controls.apply(Action::Step);
+
+        assert!(controls.paused);
+    }
+
+    #[test]
+    fn stepping_back_pauses() {
+        let mut controls = Controls::new(Duration::from_millis(30));
+
+        controls.apply(Action::Back);
+
+        assert!(controls.paused);
+    }
+
+    #[test]
+    fn speed_changes_double_or_halve_the_delay() {
+        let mut controls = Controls::new(Duration::from_millis(30));
+
+        controls.apply(Action::Faster);
+        assert_eq!(controls.delay, Duration::from_millis(15));
+        controls.apply(Action::Slower);
+        controls.apply(Action::Slower);
+        assert_eq!(controls.delay, Duration::from_millis(60));
+    }
+
+    #[test]
+    fn the_delay_stays_within_bounds() {
+        let mut controls = Controls::new(Duration::from_millis(1));
+
+        controls.apply(Action::Faster);
+        assert_eq!(controls.delay, Duration::from_millis(0));
+        controls.apply(Action::Slower);
+        assert_eq!(controls.delay, MIN_DELAY);
+        for _ in 0..20 {
+            controls.apply(Action::Slower);
+        }
+        assert_eq!(controls.delay, MAX_DELAY);
+    }
+
+    #[test]
+    fn pressing_toggles_a_cell() {
+        let mut board = GridBoard::all_alive(3);
+        let mut editor = Editor::new();
+
+        editor.press(&mut board, Coordinates { x: 1, y: 1 });
+        assert_eq!(state_at(&board, 1, 1), Some(CellState::Dead));
+        editor.release();
+        editor.press(&mut board, Coordinates { x: 1, y: 1 });
+        assert_eq!(state_at(&board, 1, 1), Some(CellState::Alive));
+    }
+
+    #[test]
+    fn dragging_paints_with_the_state_chosen_by_the_press() {
+        let mut board = GridBoard::diagonal(3, 3);
+        let mut editor = Editor::new();
+
+        editor.press(&mut board, Coordinates { x: 0, y: 2 });
+        editor.drag(&mut board, Coordinates { x: 1, y: 2 });
+        editor.drag(&mut board, Coordinates { x: 2, y: 2 });
+        editor.release();
+        editor.drag(&mut board, Coordinates { x: 2, y: 0 });
+
+        assert_eq!(state_at(&board, 0, 2), Some(CellState::Alive));
+        assert_eq!(state_at(&board, 1, 2), Some(CellState::Alive));
+        assert_eq!(state_at(&board, 2, 2), Some(CellState::Alive));
+        assert_eq!(state_at(&board, 2, 0), Some(CellState::Dead));
+    }
+}
@@ -1,16 +1,116 @@
-pub use models::{Cell, Coordinates};
+pub use models::{BoundingBox, Cell, CellState, Coordinates};
 
 pub type Rows = Vec<Vec<Cell>>;
 
 pub trait Board: Clone + From<Rows> + Sync {
     fn at(&self, coordiates: Coordinates) -> Option<Cell>;
     fn rows(&self) -> &Rows;
+
+    /// Changes the state of a single cell. Cells that aren't on the board
+    /// are left alone.
+    fn set(&mut self, coordinates: Coordinates, cell_state: CellState);
+
+    /// The locations of every living cell, including those just born.
+    fn live_cells<'a>(&'a self) -> Box<dyn Iterator<Item = Coordinates> + 'a> {
+        Box::new(
+            self.rows()
+                .iter()
+                .flat_map(|row| row.iter())
+                .filter(|cell| cell.cell_state.is_alive())
+                .map(|cell| cell.location),
+        )
+    }
+
+    fn population(&self) -> usize {
+        self.live_cells().count()
+    }
+
+    /// Every cell that was born or died in the last generation.
+    fn changed_cells<'a>(&'a self) -> Box<dyn Iterator<Item = Cell> + 'a> {
+        Box::new(
+            self.rows()
+                .iter()
+                .flat_map(|row| row.iter())
+                .filter(|cell| {
+                    cell.cell_state == CellState::Born || cell.cell_state == CellState::Died
+                })
+                .cloned(),
+        )
+    }
+
+    /// The smallest rectangle containing every living cell, or `None` when
+    /// nothing is alive.
+    fn bounding_box(&self) -> Option<BoundingBox> {
+        BoundingBox::around(self.live_cells())
+    }
+
+    /// Kills every cell, including any that have only just died.
+    fn clear(&mut self) {
+        let occupied: Vec<Coordinates> = self
+            .rows()
+            .iter()
+            .flat_map(|row| row.iter())
+            .filter(|cell| cell.cell_state != CellState::Dead)
+            .map(|cell| cell.location)
+            .collect();
+        for coordinates in occupied {
+            self.set(coordinates, CellState::Dead);
+        }
+    }
+
+    /// The width and height of the board, or `None` if it goes on forever.
+    fn size(&self) -> Option<(i32, i32)> {
+        let rows = self.rows();
+        Some((
+            rows.first().map_or(0, |row| row.len()) as i32,
+            rows.len() as i32,
+        ))
+    }
+
+    /// The width and height of the board, if its edges wrap around so that
+    /// it behaves like the surface of a torus.
+    fn torus_size(&self) -> Option<(i32, i32)> {
+        None
+    }
+
+    /// Builds the board that follows this one from freshly generated rows,
+    /// carrying over any settings that the rows themselves don't capture.
+    fn successor(&self, rows: Rows) -> Self {
+        Self::from(rows)
+    }
 }
 
 pub trait Renderer<T: Board> {
     fn render(&self, board: &T);
+
+    /// Finds the cell drawn at a 1-based terminal column and line, if the
+    /// renderer knows where it draws cells.
+    #[allow(unused_variables)]
+    fn coordinates_at(&self, board: &T, column: u16, line: u16) -> Option<Coordinates> {
+        None
+    }
 }
 
 pub trait Generator<T: Board> {
     fn generate(&self, board: &T) -> T;
 }
+
+/// Whether a game should keep going.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Control {
+    Continue,
+    Stop,
+}
+
+/// Something that watches a game, generation by generation.
+pub trait Observer<T: Board> {
+    /// Sees the board of each generation, starting with the first board as
+    /// generation 0. Returning `Control::Stop` ends the game there.
+    fn observe(&mut self, generation: u32, board: &T) -> Control;
+}
+
+impl<T: Board, F: FnMut(u32, &T) -> Control> Observer<T> for F {
+    fn observe(&mut self, generation: u32, board: &T) -> Control {
+        self(generation, board)
+    }
+}
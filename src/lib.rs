@@ -7,9 +7,20 @@ pub mod interface;
 pub mod board;
 pub mod generator;
 pub mod renderer;
+pub mod theme;
+pub mod pattern;
+pub mod rule;
+pub mod cli;
+pub mod interactive;
+pub mod cycle;
+pub mod census;
+pub mod statistics;
+pub mod history;
 mod engine;
 
-use interface::{Board, Generator, Renderer};
+use cycle::{Classification, CycleDetector};
+use interface::{Board, Control, Generator, Observer, Renderer};
+use statistics::Statistics;
 use std::{thread, time};
 
 pub struct Game<'a, T, U, V>
@@ -21,6 +32,22 @@ where
     board: T,
     renderer: &'a U,
     generator: &'a V,
+    detector: Option<CycleDetector>,
+    statistics: Option<Statistics>,
+    observers: Vec<&'a mut dyn Observer<T>>,
+}
+
+/// How a game finished.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Ending<T: Board> {
+    pub board: T,
+    /// How many generations were played.
+    pub generations: u32,
+    /// What the board had settled into, if the game stopped because of it.
+    pub classification: Option<Classification>,
+    /// Statistics for every generation played, including the first, if they
+    /// were asked for.
+    pub statistics: Option<Statistics>,
 }
 
 impl<'a, T, U, V> Game<'a, T, U, V>
@@ -34,25 +61,179 @@ where
             board,
             renderer,
             generator,
+            detector: None,
+            statistics: None,
+            observers: Vec::new(),
+        }
+    }
+
+    /// Stops the game as soon as the board dies out or starts repeating
+    /// itself, rather than playing every generation. The game keeps its own
+    /// `CycleDetector` as an observer, and reports what it found in the
+    /// game's `Ending`.
+    pub fn stop_when_periodic(self, stop: bool) -> Game<'a, T, U, V> {
+        Game {
+            detector: stop.then(CycleDetector::new),
+            ..self
+        }
+    }
+
+    /// Records the population, births, deaths, bounding box and density of
+    /// every generation, to be found in the game's `Ending`. The game keeps
+    /// its own `Statistics` as an observer.
+    pub fn record_statistics(self, record: bool) -> Game<'a, T, U, V> {
+        Game {
+            statistics: record.then(Statistics::new),
+            ..self
         }
     }
 
+    /// Adds an observer that sees every generation after the renderer does,
+    /// and can stop the game early. Observers are called in the order they
+    /// were added, after the game's own. A `CycleDetector` stops the game
+    /// once the board dies out or repeats itself, and `Statistics` records
+    /// every generation.
+    pub fn with_observer(mut self, observer: &'a mut dyn Observer<T>) -> Game<'a, T, U, V> {
+        self.observers.push(observer);
+        self
+    }
+
     pub fn play(self, generations: u32) -> T {
         self.play_with_delay(generations, time::Duration::from_millis(0))
     }
 
     pub fn play_with_delay(self, generations: u32, delay: time::Duration) -> T {
-        self.renderer.render(&self.board);
+        self.run(generations, delay).board
+    }
 
-        let mut b = self.board;
+    /// Plays up to `generations` generations, reporting how the game ended.
+    pub fn run(self, generations: u32, delay: time::Duration) -> Ending<T> {
+        let mut boards = self.generations();
+        boards.delay = delay;
+        boards.advance();
         for _ in 0..generations {
-            thread::sleep(delay);
-            b = self.generator.generate(&b);
-            self.renderer.render(&b);
+            if !boards.advance() {
+                break;
+            }
+        }
+        boards.ending()
+    }
+
+    /// The boards of the game, starting with the first one, worked out only
+    /// as they're asked for.
+    pub fn generations(self) -> Generations<'a, T, U, V> {
+        Generations {
+            game: self,
+            delay: time::Duration::from_millis(0),
+            played: None,
+            stopped: false,
+        }
+    }
+}
+
+/// The boards of a game, one generation at a time.
+///
+/// Each board is rendered and shown to the observers as it's reached. The
+/// boards run out once an observer stops the game, and otherwise go on
+/// forever.
+pub struct Generations<'a, T, U, V>
+where
+    T: Board,
+    U: 'a + Renderer<T>,
+    V: 'a + Generator<T>,
+{
+    game: Game<'a, T, U, V>,
+    delay: time::Duration,
+    played: Option<u32>,
+    stopped: bool,
+}
+
+impl<'a, T, U, V> Generations<'a, T, U, V>
+where
+    T: Board,
+    U: 'a + Renderer<T>,
+    V: 'a + Generator<T>,
+{
+    /// Moves on to the next generation, or to the first board if the game
+    /// hasn't started, unless the game is over.
+    fn advance(&mut self) -> bool {
+        if self.stopped {
+            return false;
+        }
+        let generation = match self.played {
+            None => 0,
+            Some(played) => {
+                thread::sleep(self.delay);
+                self.game.board = self.game.generator.generate(&self.game.board);
+                played + 1
+            }
+        };
+        self.played = Some(generation);
+
+        let game = &mut self.game;
+        game.renderer.render(&game.board);
+        let mut observers: Vec<&mut dyn Observer<T>> = Vec::new();
+        if let Some(ref mut detector) = game.detector {
+            observers.push(detector);
+        }
+        if let Some(ref mut statistics) = game.statistics {
+            observers.push(statistics);
+        }
+        for observer in game.observers.iter_mut() {
+            observers.push(&mut **observer);
+        }
+        if notify(&mut observers, generation, &game.board) == Control::Stop {
+            self.stopped = true;
+        }
+        true
+    }
+
+    /// How the game has gone so far, ending with the last board reached.
+    pub fn ending(self) -> Ending<T> {
+        Ending {
+            board: self.game.board,
+            generations: self.played.unwrap_or(0),
+            classification: self
+                .game
+                .detector
+                .as_ref()
+                .and_then(|detector| detector.classification()),
+            statistics: self.game.statistics,
+        }
+    }
+}
+
+impl<'a, T, U, V> Iterator for Generations<'a, T, U, V>
+where
+    T: Board,
+    U: 'a + Renderer<T>,
+    V: 'a + Generator<T>,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.advance() {
+            Some(self.game.board.clone())
+        } else {
+            None
         }
+    }
+}
 
-        b
+/// Shows a generation to every observer, and stops if any of them ask to.
+/// Every observer sees the generation, even after one has asked to stop.
+fn notify<T: Board>(
+    observers: &mut [&mut dyn Observer<T>],
+    generation: u32,
+    board: &T,
+) -> Control {
+    let mut control = Control::Continue;
+    for observer in observers.iter_mut() {
+        if observer.observe(generation, board) == Control::Stop {
+            control = Control::Stop;
+        }
     }
+    control
 }
 
 #[cfg(test)]
@@ -115,6 +296,247 @@ mod tests {
         assert_eq!(result, FakeBoard::new(1337));
     }
 
+    #[test]
+    fn games_only_stop_early_when_asked_to() {
+        let generator = FakeGenerator::new();
+        let renderer = FakeRenderer::new();
+        let board = FakeBoard::new(0);
+
+        let ending =
+            Game::new(board, &renderer, &generator).run(5, time::Duration::from_millis(0));
+
+        assert_eq!(ending.generations, 5);
+        assert_eq!(ending.classification, None);
+    }
+
+    #[test]
+    fn a_game_stops_once_the_board_repeats() {
+        use board::grid::GridBoard;
+        use generator::SimpleGenerator;
+        use renderer::NullRenderer;
+
+        let blinker = GridBoard::fill_with(
+            5,
+            5,
+            Box::new(|c| if c.y == 2 && c.x > 0 && c.x < 4 {
+                CellState::Alive
+            } else {
+                CellState::Dead
+            }),
+        );
+
+        let ending = Game::new(blinker, &NullRenderer, &SimpleGenerator::new())
+            .stop_when_periodic(true)
+            .run(100, time::Duration::from_millis(0));
+
+        assert_eq!(ending.generations, 2);
+        assert_eq!(
+            ending.classification,
+            Some(Classification::Oscillating { period: 2 })
+        );
+        assert_eq!(ending.board.population(), 3);
+    }
+
+    #[test]
+    fn the_last_generation_played_is_checked_for_repeats() {
+        use board::grid::GridBoard;
+        use generator::SimpleGenerator;
+        use renderer::NullRenderer;
+
+        let lonely = GridBoard::fill_with(
+            3,
+            3,
+            Box::new(|c| if c.x == 1 && c.y == 1 {
+                CellState::Alive
+            } else {
+                CellState::Dead
+            }),
+        );
+
+        let ending = Game::new(lonely, &NullRenderer, &SimpleGenerator::new())
+            .stop_when_periodic(true)
+            .run(1, time::Duration::from_millis(0));
+
+        assert_eq!(ending.generations, 1);
+        assert_eq!(ending.classification, Some(Classification::Extinct));
+    }
+
+    #[test]
+    fn a_cycle_detector_can_watch_a_game_as_an_observer() {
+        use board::grid::GridBoard;
+        use generator::SimpleGenerator;
+        use renderer::NullRenderer;
+
+        let lonely = GridBoard::fill_with(
+            3,
+            3,
+            Box::new(|c| {
+                if c.x == 1 && c.y == 1 {
+                    CellState::Alive
+                } else {
+                    CellState::Dead
+                }
+            }),
+        );
+        let mut detector = CycleDetector::new();
+
+        let ending = Game::new(lonely, &NullRenderer, &SimpleGenerator::new())
+            .with_observer(&mut detector)
+            .run(5, time::Duration::from_millis(0));
+
+        assert_eq!(ending.generations, 1);
+        assert_eq!(ending.classification, None);
+        assert_eq!(detector.classification(), Some(Classification::Extinct));
+    }
+
+    #[test]
+    fn statistics_are_only_recorded_when_asked_for() {
+        let generator = FakeGenerator::new();
+        let renderer = FakeRenderer::new();
+        let game = || Game::new(FakeBoard::new(0), &renderer, &generator);
+
+        let without = game().run(3, time::Duration::from_millis(0));
+        let with = game()
+            .record_statistics(true)
+            .run(3, time::Duration::from_millis(0));
+
+        assert_eq!(without.statistics, None);
+        let generations: Vec<u32> = with
+            .statistics
+            .unwrap()
+            .generations()
+            .iter()
+            .map(|g| g.generation)
+            .collect();
+        assert_eq!(generations, vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn every_observer_sees_every_generation() {
+        let generator = FakeGenerator::new();
+        let renderer = FakeRenderer::new();
+        let mut statistics = Statistics::new();
+        let mut seen = Vec::new();
+        {
+            let mut watcher = |generation: u32, _: &FakeBoard| {
+                seen.push(generation);
+                Control::Continue
+            };
+            let ending = Game::new(FakeBoard::new(0), &renderer, &generator)
+                .with_observer(&mut statistics)
+                .with_observer(&mut watcher)
+                .run(3, time::Duration::from_millis(0));
+            assert_eq!(ending.generations, 3);
+        }
+
+        assert_eq!(seen, vec![0, 1, 2, 3]);
+        assert_eq!(statistics.generations().len(), 4);
+    }
+
+    #[test]
+    fn an_observer_can_stop_the_game() {
+        let generator = FakeGenerator::new();
+        let renderer = FakeRenderer::new();
+        let mut calls = 0;
+        let mut stop_at_two = |generation: u32, _: &FakeBoard| {
+            calls += 1;
+            if generation == 2 {
+                Control::Stop
+            } else {
+                Control::Continue
+            }
+        };
+        let mut others = 0;
+        let mut other = |_: u32, _: &FakeBoard| {
+            others += 1;
+            Control::Continue
+        };
+
+        let ending = Game::new(FakeBoard::new(0), &renderer, &generator)
+            .with_observer(&mut stop_at_two)
+            .with_observer(&mut other)
+            .run(100, time::Duration::from_millis(0));
+
+        assert_eq!(ending.generations, 2);
+        assert_eq!(ending.classification, None);
+        assert_eq!(renderer.boards.borrow().len(), 3);
+        assert_eq!((calls, others), (3, 3));
+    }
+
+    #[test]
+    fn generations_are_worked_out_as_they_are_needed() {
+        let generator = FakeGenerator::new();
+        let renderer = FakeRenderer::new();
+
+        let boards: Vec<FakeBoard> = Game::new(FakeBoard::new(0), &renderer, &generator)
+            .generations()
+            .take(3)
+            .collect();
+
+        assert_eq!(
+            boards,
+            vec![FakeBoard::new(0), FakeBoard::new(1337), FakeBoard::new(1337)]
+        );
+        assert_eq!(generator.boards.borrow().len(), 2);
+        assert_eq!(renderer.boards.borrow().len(), 3);
+    }
+
+    #[test]
+    fn generations_work_with_iterator_adaptors() {
+        use board::grid::GridBoard;
+        use generator::SimpleGenerator;
+        use renderer::NullRenderer;
+
+        let glider = GridBoard::fill_with(
+            8,
+            8,
+            Box::new(|c| match (c.x, c.y) {
+                (1, 0) | (2, 1) | (0, 2) | (1, 2) | (2, 2) => CellState::Alive,
+                _ => CellState::Dead,
+            }),
+        );
+        let generator = SimpleGenerator::new();
+
+        let every_fourth: Vec<Option<BoundingBox>> = Game::new(glider, &NullRenderer, &generator)
+            .generations()
+            .step_by(4)
+            .take(3)
+            .map(|board| board.bounding_box())
+            .collect();
+
+        let corners: Vec<Coordinates> = every_fourth.iter().map(|b| b.unwrap().min).collect();
+        assert_eq!(
+            corners,
+            vec![
+                Coordinates { x: 0, y: 0 },
+                Coordinates { x: 1, y: 1 },
+                Coordinates { x: 2, y: 2 },
+            ]
+        );
+    }
+
+    #[test]
+    fn generations_end_when_the_game_stops() {
+        let generator = FakeGenerator::new();
+        let renderer = FakeRenderer::new();
+        let mut stop_at_one = |generation: u32, _: &FakeBoard| {
+            if generation == 1 {
+                Control::Stop
+            } else {
+                Control::Continue
+            }
+        };
+
+        let mut boards = Game::new(FakeBoard::new(0), &renderer, &generator)
+            .with_observer(&mut stop_at_one)
+            .generations();
+
+        assert_eq!(boards.by_ref().count(), 2);
+        let ending = boards.ending();
+        assert_eq!(ending.generations, 1);
+        assert_eq!(ending.board, FakeBoard::new(1337));
+    }
+
     #[derive(Debug, PartialEq, Eq, Clone, Hash)]
     struct FakeBoard {
         seed: i32,
@@ -136,6 +558,9 @@ mod tests {
         fn rows(&self) -> &Rows {
             &self.rows
         }
+
+        #[allow(unused_variables)]
+        fn set(&mut self, coordinates: Coordinates, cell_state: CellState) {}
     }
 
     impl From<Rows> for FakeBoard {
@@ -1,20 +1,204 @@
-extern crate termion;
 extern crate life;
-use life::renderer::StringRenderer;
+extern crate rand;
+extern crate termion;
 use life::board::grid::*;
+use life::board::sparse::SparseBoard;
+use life::board::Topology;
+use life::census::CensusTaker;
+use life::cli::{self, Command, Options, RendererKind, Space};
 use life::generator::*;
+use life::interactive::Interactive;
+use life::interface::{Board, Coordinates, Renderer};
+use life::pattern::Format;
+use life::renderer::{
+    BrailleRenderer, HalfBlockRenderer, NullRenderer, StringRenderer, ViewportRenderer,
+};
+use life::rule::Rule;
+use life::theme::Theme;
+use rand::Rng;
 use std::io::stdout;
-
-use std::time;
+use std::{env, fs, process};
 
 fn main() {
-    let size = termion::terminal_size().unwrap_or((100, 100));
+    let options = match cli::parse(env::args().skip(1)) {
+        Ok(Command::Run(options)) => options,
+        Ok(Command::Help) => {
+            println!("{}", cli::USAGE);
+            return;
+        }
+        Err(e) => {
+            eprintln!("game: {}\nTry 'game --help' for more information.", e);
+            process::exit(2);
+        }
+    };
+
+    if let Err(e) = run(&options) {
+        eprintln!("game: {}", e);
+        process::exit(1);
+    }
+}
 
-    let renderer = StringRenderer::new(stdout(), size.0, size.1);
-    let board = GridBoard::random(size.0.into(), size.1.into());
-    let generator = SimpleGenerator {};
+fn run(options: &Options) -> Result<(), String> {
+    let pattern = match options.pattern {
+        Some(ref path) => {
+            let contents = fs::read_to_string(path)
+                .map_err(|e| format!("couldn't read {}: {}", path.display(), e))?;
+            let pattern = Format::detect(path, &contents)
+                .parse(&contents)
+                .map_err(|e| format!("{}: {}", path.display(), e))?;
+            Some(pattern)
+        }
+        None => None,
+    };
+
+    let theme = match options.theme {
+        Some(ref name) => match Theme::preset(name) {
+            Some(theme) => theme,
+            None => {
+                let contents = fs::read_to_string(name)
+                    .map_err(|e| format!("couldn't read theme {}: {}", name, e))?;
+                Theme::parse(&contents).map_err(|e| format!("{}: {}", name, e))?
+            }
+        },
+        None => Theme::default(),
+    };
+
+    let terminal = termion::terminal_size().unwrap_or((100, 100));
+    let pattern_size = pattern
+        .as_ref()
+        .map(|p| (p.board.width(), p.board.height()));
+    let (width, height) = options
+        .board_size(terminal, pattern_size)
+        .map_err(|e| e.to_string())?;
+    let rule = options
+        .rule
+        .or_else(|| pattern.as_ref().and_then(|p| p.rule))
+        .unwrap_or_default();
+
+    let (board_width, board_height) = (width.into(), height.into());
+    let (density, symmetry) = (options.density, options.symmetry);
+    let soup = move |seed| {
+        GridBoard::symmetric_random(board_width, board_height, seed, density, symmetry)
+    };
+    let seed = options.seed.unwrap_or_else(|| rand::thread_rng().gen());
+
+    match options.space {
+        Space::Bounded | Space::Torus => {
+            let topology = if options.space == Space::Torus {
+                Topology::Toroidal
+            } else {
+                Topology::Bounded
+            };
+            let board = match pattern {
+                Some(ref pattern) => pattern.centred_on(board_width, board_height),
+                None => soup(seed),
+            }
+            .with_topology(topology);
+            let reseed = || soup(rand::thread_rng().gen()).with_topology(topology);
+            match options.renderer {
+                RendererKind::String => play(
+                    board,
+                    &StringRenderer::new(stdout(), width, height).with_theme(&theme),
+                    rule,
+                    options,
+                    reseed,
+                ),
+                RendererKind::Braille => play(
+                    board,
+                    &BrailleRenderer::new(stdout(), width, height),
+                    rule,
+                    options,
+                    reseed,
+                ),
+                RendererKind::HalfBlock => play(
+                    board,
+                    &HalfBlockRenderer::new(stdout(), width, height),
+                    rule,
+                    options,
+                    reseed,
+                ),
+                RendererKind::None => play(board, &NullRenderer, rule, options, reseed),
+            }
+        }
+        Space::Plane => {
+            let board = match pattern {
+                Some(ref pattern) => {
+                    SparseBoard::with_live_cells(pattern.centred_cells(board_width, board_height))
+                }
+                None => SparseBoard::with_live_cells(soup(seed).live_cells()),
+            };
+            let reseed =
+                || SparseBoard::with_live_cells(soup(rand::thread_rng().gen()).live_cells());
+            match options.renderer {
+                RendererKind::String => {
+                    let renderer = ViewportRenderer::new(
+                        StringRenderer::new(stdout(), width, height).with_theme(&theme),
+                        Coordinates { x: 0, y: 0 },
+                        board_width,
+                        board_height,
+                    );
+                    play(board, &renderer, rule, options, reseed)
+                }
+                RendererKind::Braille => {
+                    let renderer = ViewportRenderer::new(
+                        BrailleRenderer::new(stdout(), width, height),
+                        Coordinates { x: 0, y: 0 },
+                        board_width,
+                        board_height,
+                    );
+                    play(board, &renderer, rule, options, reseed)
+                }
+                RendererKind::HalfBlock => {
+                    let renderer = ViewportRenderer::new(
+                        HalfBlockRenderer::new(stdout(), width, height),
+                        Coordinates { x: 0, y: 0 },
+                        board_width,
+                        board_height,
+                    );
+                    play(board, &renderer, rule, options, reseed)
+                }
+                RendererKind::None => play(board, &NullRenderer, rule, options, reseed),
+            }
+        }
+    }
+}
 
-    let game = life::Game::new(board, &renderer, &generator);
-    //game.play(1000);
-    game.play_with_delay(1000, time::Duration::from_millis(30));
+fn play<T, R, F>(
+    board: T,
+    renderer: &R,
+    rule: Rule,
+    options: &Options,
+    reseed: F,
+) -> Result<(), String>
+where
+    T: Board,
+    R: Renderer<T>,
+    F: FnMut() -> T,
+{
+    let generator = &SimpleGenerator::with_rule(rule);
+    if options.interactive {
+        Interactive::new(board, renderer, generator, options.delay)
+            .play(reseed)
+            .map_err(|e| format!("terminal error: {}", e))?;
+    } else {
+        let ending = life::Game::new(board, renderer, generator)
+            .stop_when_periodic(options.stop_when_periodic)
+            .record_statistics(options.statistics.is_some())
+            .run(options.generations, options.delay);
+        if let Some(classification) = ending.classification {
+            println!("{} after {} generations", classification, ending.generations);
+        }
+        if options.census {
+            print!("{}", CensusTaker::with_rule(rule).take(&ending.board));
+        }
+        if let (Some(path), Some(statistics)) = (options.statistics.as_ref(), ending.statistics) {
+            let contents = match path.extension().and_then(|e| e.to_str()) {
+                Some("json") => statistics.to_json(),
+                _ => statistics.to_csv(),
+            };
+            fs::write(path, contents)
+                .map_err(|e| format!("couldn't write {}: {}", path.display(), e))?;
+        }
+    }
+    Ok(())
 }
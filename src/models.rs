@@ -52,6 +52,47 @@ impl Coordinates {
     }
 }
 
+/// The smallest rectangle containing a set of cells. Both corners are
+/// inclusive.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct BoundingBox {
+    pub min: Coordinates,
+    pub max: Coordinates,
+}
+
+impl BoundingBox {
+    pub fn around<I: IntoIterator<Item = Coordinates>>(cells: I) -> Option<BoundingBox> {
+        cells.into_iter().fold(None, |bounds, c| {
+            Some(match bounds {
+                None => BoundingBox { min: c, max: c },
+                Some(BoundingBox { min, max }) => BoundingBox {
+                    min: Coordinates {
+                        x: min.x.min(c.x),
+                        y: min.y.min(c.y),
+                    },
+                    max: Coordinates {
+                        x: max.x.max(c.x),
+                        y: max.y.max(c.y),
+                    },
+                },
+            })
+        })
+    }
+
+    pub fn width(&self) -> i32 {
+        self.max.x - self.min.x + 1
+    }
+
+    pub fn height(&self) -> i32 {
+        self.max.y - self.min.y + 1
+    }
+
+    pub fn contains(&self, coordinates: Coordinates) -> bool {
+        coordinates.x >= self.min.x && coordinates.x <= self.max.x && coordinates.y >= self.min.y
+            && coordinates.y <= self.max.y
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub struct Cell {
     pub cell_state: CellState,
@@ -66,6 +107,13 @@ pub enum CellState {
     Died,
 }
 
+impl CellState {
+    /// Whether the cell counts as living, having just been born or not.
+    pub fn is_alive(&self) -> bool {
+        *self == CellState::Alive || *self == CellState::Born
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -94,4 +142,20 @@ mod tests {
 
         assert_eq!(expected, result);
     }
+
+    #[test]
+    fn bounding_box_around_cells() {
+        let bounds = BoundingBox::around(vec![
+            Coordinates { x: 2, y: -1 },
+            Coordinates { x: -3, y: 4 },
+            Coordinates { x: 0, y: 0 },
+        ]).unwrap();
+
+        assert_eq!(bounds.min, Coordinates { x: -3, y: -1 });
+        assert_eq!(bounds.max, Coordinates { x: 2, y: 4 });
+        assert_eq!((bounds.width(), bounds.height()), (6, 6));
+        assert!(bounds.contains(Coordinates { x: 0, y: 4 }));
+        assert!(!bounds.contains(Coordinates { x: 3, y: 0 }));
+        assert_eq!(BoundingBox::around(vec![]), None);
+    }
 }
@@ -0,0 +1,330 @@
+//! Apgcodes, the short names soup searches give to the objects they find,
+//! such as `xs4_33` for the block or `xq4_153` for the glider.
+//!
+//! The prefix says what kind of object it is: `xs` and the population for a
+//! still life, `xp` and the period for an oscillator, and `xq` and the period
+//! for a spaceship. After the underscore, the object is cut into strips five
+//! rows tall, and each column of a strip is written as a character from
+//! `0123456789abcdefghijklmnopqrstuv`, with its top cell as the lowest bit.
+//! Strips are separated by `z`, trailing empty columns are left out and runs
+//! of empty columns are shortened: `w` is two, `x` is three and `y` followed
+//! by a character `c` is four more than the value of `c`.
+//!
+//! The same object can be written in many ways, one for each orientation and
+//! phase, so the canonical code is the shortest of them, with ties going to
+//! whichever comes first alphabetically.
+
+use super::canonical::{orient, Kind};
+use super::{Pattern, PatternError};
+use board::grid::GridBoard;
+use models::Coordinates;
+
+const DIGITS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+const STRIP_HEIGHT: i32 = 5;
+const LONGEST_GAP: usize = 39;
+
+/// The canonical apgcode of an object, given the living cells of each of its
+/// phases. Objects that aren't still lifes, oscillators or spaceships have no
+/// apgcode.
+pub fn encode(kind: Kind, phases: &[Vec<Coordinates>]) -> Option<String> {
+    let prefix = match kind {
+        Kind::StillLife => format!("xs{}", phases.first().map_or(0, |cells| cells.len())),
+        Kind::Oscillator { period } => format!("xp{}", period),
+        Kind::Spaceship { period } => format!("xq{}", period),
+        Kind::Unstable => return None,
+    };
+    let body = phases
+        .iter()
+        .flat_map(|cells| (0..8).map(move |orientation| oriented(cells, orientation)))
+        .map(|cells| wechsler(&cells))
+        .min_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)))?;
+    Some(format!("{}_{}", prefix, body))
+}
+
+/// Turns the cells and moves them so that the top left corner is at the
+/// origin.
+fn oriented(cells: &[Coordinates], orientation: u8) -> Vec<Coordinates> {
+    let turned: Vec<Coordinates> = cells.iter().map(|&c| orient(c, orientation)).collect();
+    let min_x = turned.iter().map(|c| c.x).min().unwrap_or(0);
+    let min_y = turned.iter().map(|c| c.y).min().unwrap_or(0);
+    turned
+        .into_iter()
+        .map(|c| Coordinates {
+            x: c.x - min_x,
+            y: c.y - min_y,
+        })
+        .collect()
+}
+
+/// Writes cells with their top left corner at the origin in the part of an
+/// apgcode after the underscore.
+fn wechsler(cells: &[Coordinates]) -> String {
+    let width = cells.iter().map(|c| c.x + 1).max().unwrap_or(0) as usize;
+    let strips = cells
+        .iter()
+        .map(|c| c.y / STRIP_HEIGHT + 1)
+        .max()
+        .unwrap_or(0) as usize;
+    let mut columns = vec![vec![0u8; width]; strips];
+    for cell in cells {
+        let strip = (cell.y / STRIP_HEIGHT) as usize;
+        columns[strip][cell.x as usize] |= 1 << (cell.y % STRIP_HEIGHT);
+    }
+
+    let mut output = String::new();
+    for (index, strip) in columns.iter().enumerate() {
+        if index > 0 {
+            output.push('z');
+        }
+        let mut gap = 0;
+        for &column in strip {
+            if column == 0 {
+                gap += 1;
+                continue;
+            }
+            push_gap(&mut output, gap);
+            gap = 0;
+            output.push(DIGITS[column as usize] as char);
+        }
+    }
+    output
+}
+
+/// Writes a run of empty columns as briefly as possible.
+fn push_gap(output: &mut String, mut gap: usize) {
+    while gap > 0 {
+        let run = gap.min(LONGEST_GAP);
+        match run {
+            1 => output.push('0'),
+            2 => output.push('w'),
+            3 => output.push('x'),
+            _ => {
+                output.push('y');
+                output.push(DIGITS[run - 4] as char);
+            }
+        }
+        gap -= run;
+    }
+}
+
+/// What kind of object an apgcode describes, from its prefix.
+pub fn kind_of(code: &str) -> Result<Kind, PatternError> {
+    split(code).map(|(kind, _, _)| kind)
+}
+
+/// Splits an apgcode into its kind, the population of a still life, and the
+/// part after the underscore.
+fn split(code: &str) -> Result<(Kind, Option<usize>, &str), PatternError> {
+    let underscore = code
+        .find('_')
+        .ok_or_else(|| PatternError::new(1, 1, "expected an underscore after the prefix"))?;
+    let (prefix, body) = (&code[..underscore], &code[underscore + 1..]);
+    let number = prefix.get(2..).and_then(|n| n.parse::<u32>().ok());
+    let kind = match (prefix.get(..2), number) {
+        (Some("xs"), Some(_)) => Kind::StillLife,
+        (Some("xp"), Some(period)) if period > 0 => Kind::Oscillator { period },
+        (Some("xq"), Some(period)) if period > 0 => Kind::Spaceship { period },
+        _ => {
+            return Err(PatternError::new(
+                1,
+                1,
+                format!("unknown apgcode prefix '{}'", prefix),
+            ))
+        }
+    };
+    let population = match kind {
+        Kind::StillLife => number.map(|n| n as usize),
+        _ => None,
+    };
+    Ok((kind, population, body))
+}
+
+/// Reads the living cells of an apgcode, with the top left corner of the
+/// object at the origin.
+pub fn parse_coordinates(code: &str) -> Result<Vec<Coordinates>, PatternError> {
+    let (_, population, body) = split(code)?;
+    let offset = code.len() - body.len();
+
+    let mut cells = Vec::new();
+    let (mut x, mut strip) = (0, 0);
+    let mut characters = body.char_indices();
+    while let Some((index, character)) = characters.next() {
+        let column = offset + index + 1;
+        let value = DIGITS.iter().position(|&d| d as char == character);
+        match (character, value) {
+            ('w', _) => x += 2,
+            ('x', _) => x += 3,
+            ('y', _) => match characters
+                .next()
+                .and_then(|(_, c)| DIGITS.iter().position(|&d| d as char == c))
+            {
+                Some(gap) => x += 4 + gap as i32,
+                None => {
+                    return Err(PatternError::new(
+                        1,
+                        column,
+                        "expected the length of a gap after 'y'",
+                    ))
+                }
+            },
+            ('z', _) => {
+                x = 0;
+                strip += 1;
+            }
+            (_, Some(bits)) => {
+                for bit in 0..STRIP_HEIGHT {
+                    if bits & (1 << bit) != 0 {
+                        cells.push(Coordinates {
+                            x,
+                            y: strip * STRIP_HEIGHT + bit,
+                        });
+                    }
+                }
+                x += 1;
+            }
+            _ => {
+                return Err(PatternError::new(
+                    1,
+                    column,
+                    format!("unexpected '{}' in apgcode", character),
+                ))
+            }
+        }
+    }
+
+    if cells.is_empty() {
+        return Err(PatternError::new(
+            1,
+            offset + 1,
+            "the apgcode has no living cells",
+        ));
+    }
+    match population {
+        Some(population) if population != cells.len() => Err(PatternError::new(
+            1,
+            1,
+            format!(
+                "the prefix says {} cells but the apgcode has {}",
+                population,
+                cells.len()
+            ),
+        )),
+        _ => Ok(cells),
+    }
+}
+
+/// Reads an apgcode into a pattern named after it.
+pub fn parse(code: &str) -> Result<Pattern, PatternError> {
+    let code = code.trim();
+    let cells = parse_coordinates(code)?;
+    Ok(Pattern {
+        name: Some(code.to_string()),
+        ..Pattern::from_live_cells(&cells)?
+    })
+}
+
+/// Reads an apgcode into a board just large enough to hold the object.
+pub fn decode(code: &str) -> Result<GridBoard, PatternError> {
+    parse(code).map(|pattern| pattern.board)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use generator::SimpleGenerator;
+    use interface::{Board, Generator};
+    use pattern::canonical::cells_in;
+
+    #[test]
+    fn encodes_still_lifes_oscillators_and_spaceships() {
+        assert_eq!(
+            encode(Kind::StillLife, &[cells_in("OO/OO")]),
+            Some("xs4_33".to_string())
+        );
+        assert_eq!(
+            encode(Kind::StillLife, &[cells_in(".OO./O..O/.OO.")]),
+            Some("xs6_696".to_string())
+        );
+        assert_eq!(
+            encode(
+                Kind::Oscillator { period: 2 },
+                &[cells_in("OOO"), cells_in("O/O/O")]
+            ),
+            Some("xp2_7".to_string())
+        );
+        assert_eq!(encode(Kind::Unstable, &[cells_in("O")]), None);
+    }
+
+    #[test]
+    fn the_canonical_code_is_the_shortest_over_every_phase() {
+        let generator = SimpleGenerator::new();
+        let mut board = Pattern::from_live_cells(&cells_in(".O..O/O..../O...O/OOOO."))
+            .unwrap()
+            .centred_on(20, 20);
+        let mut phases = Vec::new();
+        for _ in 0..4 {
+            phases.push(board.live_cells().collect());
+            board = generator.generate(&board);
+        }
+
+        assert_eq!(
+            encode(Kind::Spaceship { period: 4 }, &phases),
+            Some("xq4_6frc".to_string())
+        );
+    }
+
+    #[test]
+    fn long_gaps_and_tall_objects_are_shortened() {
+        let mut wide = cells_in("O");
+        wide.push(Coordinates { x: 45, y: 0 });
+        wide.push(Coordinates { x: 0, y: 7 });
+
+        assert_eq!(wechsler(&wide), "1yzy11z4");
+        assert_eq!(parse_coordinates("xs3_1yzy11z4").map(|c| c.len()), Ok(3));
+    }
+
+    #[test]
+    fn decodes_to_a_board_that_can_be_run() {
+        let glider = decode("xq4_153").unwrap();
+        assert_eq!(glider.population(), 5);
+        assert_eq!((glider.width(), glider.height()), (3, 3));
+
+        let pattern = Pattern::new(glider);
+        let mut board = pattern.centred_on(12, 12);
+        let generator = SimpleGenerator::new();
+        for _ in 0..4 {
+            board = generator.generate(&board);
+        }
+        assert_eq!(board.population(), 5);
+        assert_eq!(
+            encode(
+                Kind::Spaceship { period: 4 },
+                &[board.live_cells().collect()]
+            ),
+            Some("xq4_153".to_string())
+        );
+    }
+
+    #[test]
+    fn codes_survive_a_round_trip() {
+        for &code in &["xs4_33", "xs6_696", "xs7_2596", "xp2_7", "xq4_6frc"] {
+            let cells = parse_coordinates(code).unwrap();
+            let kind = kind_of(code).unwrap();
+            assert_eq!(encode(kind, &[cells]), Some(code.to_string()), "{}", code);
+        }
+    }
+
+    #[test]
+    fn malformed_codes_are_rejected() {
+        assert_eq!(
+            kind_of("xr4_33").unwrap_err().message,
+            "unknown apgcode prefix 'xr4'"
+        );
+        assert!(decode("xs4").is_err());
+        assert!(decode("xp0_7").is_err());
+        assert_eq!(decode("xs4_3!").unwrap_err().column, 6);
+        assert_eq!(decode("xs4_3y").unwrap_err().column, 6);
+        assert!(decode("xs5_33").is_err());
+        assert!(decode("xp2_").is_err());
+    }
+}
@@ -0,0 +1,69 @@
+//! What censuses and apgcodes agree on about objects: how they behave, and
+//! the orientations they can be turned to.
+
+use models::Coordinates;
+
+/// How an object behaves on its own.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Kind {
+    StillLife,
+    Oscillator {
+        period: u32,
+    },
+    Spaceship {
+        period: u32,
+    },
+    /// Dies out, or doesn't settle down in the time allowed.
+    Unstable,
+}
+
+/// One of the eight rotations and reflections of the square, numbered from
+/// 0 to 7.
+pub fn orient(c: Coordinates, orientation: u8) -> Coordinates {
+    let (x, y) = if orientation & 4 == 0 {
+        (c.x, c.y)
+    } else {
+        (c.y, c.x)
+    };
+    Coordinates {
+        x: if orientation & 1 == 0 { x } else { -x },
+        y: if orientation & 2 == 0 { y } else { -y },
+    }
+}
+
+/// The living cells of a plaintext drawing, with rows separated by `/`.
+pub fn cells_in(drawing: &str) -> Vec<Coordinates> {
+    drawing
+        .split('/')
+        .enumerate()
+        .flat_map(|(y, row)| {
+            row.chars()
+                .enumerate()
+                .filter(|&(_, c)| c == 'O')
+                .map(move |(x, _)| Coordinates {
+                    x: x as i32,
+                    y: y as i32,
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_eight_orientations_are_distinct() {
+        let mut turned: Vec<Coordinates> = (0..8)
+            .map(|o| orient(Coordinates { x: 1, y: 2 }, o))
+            .collect();
+        turned.sort_by_key(|c| (c.y, c.x));
+        turned.dedup();
+
+        assert_eq!(turned.len(), 8);
+        assert_eq!(
+            cells_in(".O/O."),
+            vec![Coordinates { x: 1, y: 0 }, Coordinates { x: 0, y: 1 }]
+        );
+    }
+}
@@ -0,0 +1,144 @@
+//! The Life 1.06 format, a header line followed by the `x y` coordinates of
+//! each living cell.
+//!
+//! ```text
+//! #Life 1.06
+//! 0 -1
+//! 1 0
+//! -1 1
+//! 0 1
+//! 1 1
+//! ```
+
+use super::{Pattern, PatternError};
+use models::Coordinates;
+
+pub const HEADER: &str = "#Life 1.06";
+
+/// Reads the living cells exactly as they appear in the file, which may
+/// include negative coordinates.
+pub fn parse_coordinates(input: &str) -> Result<Vec<Coordinates>, PatternError> {
+    let mut lines = input.lines().enumerate();
+    match lines.next() {
+        Some((_, line)) if line.trim() == HEADER => {}
+        _ => {
+            return Err(PatternError::new(
+                1,
+                1,
+                format!("expected the '{}' header", HEADER),
+            ))
+        }
+    }
+
+    let mut cells = Vec::new();
+    for (index, line) in lines {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        let mut column = 1 + line.len() - line.trim_start().len();
+        let mut values = Vec::new();
+        for value in trimmed.split_whitespace() {
+            let offset = line[column - 1..].find(value).unwrap_or(0);
+            column += offset;
+            let parsed = value.parse::<i32>().map_err(|_| {
+                PatternError::new(index + 1, column, format!("invalid coordinate '{}'", value))
+            })?;
+            values.push(parsed);
+            column += value.len();
+        }
+
+        match values.as_slice() {
+            &[x, y] => cells.push(Coordinates { x, y }),
+            _ => {
+                return Err(PatternError::new(
+                    index + 1,
+                    1,
+                    format!("expected 'x y', found '{}'", trimmed),
+                ))
+            }
+        }
+    }
+    Ok(cells)
+}
+
+/// Reads the living cells into a pattern whose top left corner is the
+/// top left living cell.
+pub fn parse(input: &str) -> Result<Pattern, PatternError> {
+    parse_coordinates(input).and_then(|cells| Pattern::from_live_cells(&cells))
+}
+
+pub fn coordinates_to_string(cells: &[Coordinates]) -> String {
+    let mut output = format!("{}\n", HEADER);
+    for cell in cells {
+        output.push_str(&format!("{} {}\n", cell.x, cell.y));
+    }
+    output
+}
+
+pub fn to_string(pattern: &Pattern) -> String {
+    coordinates_to_string(&pattern.live_cells())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GLIDER: &str = "#Life 1.06\n0 -1\n1 0\n-1 1\n0 1\n1 1\n";
+
+    #[test]
+    fn parses_negative_coordinates() {
+        let cells = parse_coordinates(GLIDER).unwrap();
+
+        assert_eq!(cells[0], Coordinates { x: 0, y: -1 });
+        assert_eq!(cells[2], Coordinates { x: -1, y: 1 });
+        assert_eq!(cells.len(), 5);
+    }
+
+    #[test]
+    fn patterns_are_translated_to_the_origin() {
+        let pattern = parse(GLIDER).unwrap();
+
+        assert_eq!(pattern.board.width(), 3);
+        assert_eq!(pattern.board.height(), 3);
+        assert_eq!(to_string(&pattern), "#Life 1.06\n1 0\n2 1\n0 2\n1 2\n2 2\n");
+    }
+
+    #[test]
+    fn writes_what_it_reads() {
+        let cells = parse_coordinates(GLIDER).unwrap();
+
+        assert_eq!(coordinates_to_string(&cells), GLIDER);
+    }
+
+    #[test]
+    fn cells_spread_too_far_apart_are_rejected() {
+        assert_eq!(
+            parse("#Life 1.06\n-1000000000 0\n1000000000 0\n"),
+            Err(PatternError::new(
+                1,
+                1,
+                "the pattern is 2000000001x1 cells, more than the 16777216 allowed"
+            ))
+        );
+        assert!(parse("#Life 1.06\n-2147483648 0\n2147483647 0\n").is_err());
+    }
+
+    #[test]
+    fn requires_the_header() {
+        assert_eq!(parse_coordinates("0 0\n").unwrap_err().line, 1);
+    }
+
+    #[test]
+    fn reports_where_the_coordinates_are_wrong() {
+        assert_eq!(
+            parse_coordinates("#Life 1.06\n0 0\n 12  y\n"),
+            Err(PatternError::new(3, 6, "invalid coordinate 'y'"))
+        );
+        assert_eq!(
+            parse_coordinates("#Life 1.06\n0 0 0\n").unwrap_err().line,
+            2
+        );
+    }
+}
@@ -0,0 +1,282 @@
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt;
+use std::path::Path;
+
+use board::grid::GridBoard;
+use interface::Board;
+use models::*;
+use rule::Rule;
+
+pub mod apgcode;
+pub mod canonical;
+pub mod life106;
+pub mod plaintext;
+pub mod rle;
+
+/// The most cells, living or dead, that a pattern can cover. A pattern is
+/// held as a `GridBoard` of every cell in its bounding box, so a file whose
+/// pattern would be any bigger is rejected instead of allocated.
+pub const MAX_CELLS: u64 = 1 << 24;
+
+/// The pattern file formats that can be read and written.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Format {
+    Rle,
+    Plaintext,
+    Life106,
+}
+
+impl Format {
+    /// Guesses the format from a file extension, falling back to the
+    /// contents of the file.
+    pub fn detect(path: &Path, contents: &str) -> Format {
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_lowercase());
+        match extension.as_deref() {
+            Some("rle") => Format::Rle,
+            Some("cells") => Format::Plaintext,
+            Some("lif") | Some("life") if contents.starts_with(life106::HEADER) => Format::Life106,
+            _ => Format::from_contents(contents),
+        }
+    }
+
+    fn from_contents(contents: &str) -> Format {
+        let first_line = contents
+            .lines()
+            .find(|l| !l.trim().is_empty())
+            .unwrap_or("");
+        if first_line.starts_with(life106::HEADER) {
+            Format::Life106
+        } else if first_line.starts_with('!')
+            || first_line.starts_with('.')
+            || first_line.starts_with('O')
+        {
+            Format::Plaintext
+        } else {
+            Format::Rle
+        }
+    }
+
+    pub fn parse(self, input: &str) -> Result<Pattern, PatternError> {
+        match self {
+            Format::Rle => rle::parse(input),
+            Format::Plaintext => plaintext::parse(input),
+            Format::Life106 => life106::parse(input),
+        }
+    }
+
+    pub fn write(self, pattern: &Pattern) -> String {
+        match self {
+            Format::Rle => rle::to_string(pattern),
+            Format::Plaintext => plaintext::to_string(pattern),
+            Format::Life106 => life106::to_string(pattern),
+        }
+    }
+}
+
+/// A board loaded from, or ready to be saved to, a pattern file.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Pattern {
+    pub name: Option<String>,
+    pub comments: Vec<String>,
+    pub rule: Option<Rule>,
+    pub board: GridBoard,
+}
+
+impl Pattern {
+    pub fn new(board: GridBoard) -> Pattern {
+        Pattern {
+            name: None,
+            comments: Vec::new(),
+            rule: None,
+            board,
+        }
+    }
+
+    /// Builds a pattern just large enough to hold `live_cells`, translated so
+    /// that its top left corner is at the origin.
+    ///
+    /// Fails if the cells are spread over more than `MAX_CELLS`.
+    pub fn from_live_cells(live_cells: &[Coordinates]) -> Result<Pattern, PatternError> {
+        let bounds = match BoundingBox::around(live_cells.iter().cloned()) {
+            Some(bounds) => bounds,
+            None => return Ok(Pattern::new(board_with_live_cells(0, 0, HashSet::new()))),
+        };
+        let width = i64::from(bounds.max.x) - i64::from(bounds.min.x) + 1;
+        let height = i64::from(bounds.max.y) - i64::from(bounds.min.y) + 1;
+        check_size(width, height, 1, 1)?;
+
+        let translated = live_cells
+            .iter()
+            .map(|c| Coordinates {
+                x: c.x - bounds.min.x,
+                y: c.y - bounds.min.y,
+            })
+            .collect();
+        Ok(Pattern::new(board_with_live_cells(
+            width as i32,
+            height as i32,
+            translated,
+        )))
+    }
+
+    /// Places the pattern in the middle of an empty `width` x `height` board,
+    /// clipping whatever doesn't fit.
+    pub fn centred_on(&self, width: i32, height: i32) -> GridBoard {
+        let translated = self.centred_cells(width, height).into_iter().collect();
+        board_with_live_cells(width, height, translated)
+    }
+
+    /// The living cells, moved to the middle of a `width` x `height` area.
+    /// Cells that don't fit in the area are kept, outside it.
+    pub fn centred_cells(&self, width: i32, height: i32) -> Vec<Coordinates> {
+        let dx = (width - self.board.width()) / 2;
+        let dy = (height - self.board.height()) / 2;
+        self.live_cells()
+            .into_iter()
+            .map(|c| Coordinates {
+                x: c.x + dx,
+                y: c.y + dy,
+            })
+            .collect()
+    }
+
+    pub fn live_cells(&self) -> Vec<Coordinates> {
+        self.board.live_cells().collect()
+    }
+}
+
+/// Checks that a `width` x `height` pattern holds no more than `MAX_CELLS`
+/// cells, blaming any problem on the 1-based `line` and `column`.
+fn check_size(width: i64, height: i64, line: usize, column: usize) -> Result<(), PatternError> {
+    match (width as u64).checked_mul(height as u64) {
+        Some(cells) if width >= 0 && height >= 0 && cells <= MAX_CELLS => Ok(()),
+        _ => Err(PatternError::new(
+            line,
+            column,
+            format!(
+                "the pattern is {}x{} cells, more than the {} allowed",
+                width, height, MAX_CELLS
+            ),
+        )),
+    }
+}
+
+/// Builds a `width` x `height` board where only `live_cells` are alive.
+fn board_with_live_cells(width: i32, height: i32, live_cells: HashSet<Coordinates>) -> GridBoard {
+    GridBoard::fill_with(
+        width,
+        height,
+        Box::new(move |c| {
+            if live_cells.contains(&c) {
+                CellState::Alive
+            } else {
+                CellState::Dead
+            }
+        }),
+    )
+}
+
+/// A malformed pattern file, with the 1-based line and column of the problem.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct PatternError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl PatternError {
+    fn new<S: Into<String>>(line: usize, column: usize, message: S) -> PatternError {
+        PatternError {
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for PatternError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl Error for PatternError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_format_is_detected_from_the_extension_or_contents() {
+        assert_eq!(
+            Format::detect(Path::new("glider.rle"), "#C\nx = 0, y = 0"),
+            Format::Rle
+        );
+        assert_eq!(
+            Format::detect(Path::new("glider.cells"), "O"),
+            Format::Plaintext
+        );
+        assert_eq!(
+            Format::detect(Path::new("glider"), "#Life 1.06\n0 0"),
+            Format::Life106
+        );
+        assert_eq!(
+            Format::detect(Path::new("glider.txt"), "!Name: Glider"),
+            Format::Plaintext
+        );
+        assert_eq!(
+            Format::detect(Path::new("glider.txt"), "#N Glider"),
+            Format::Rle
+        );
+    }
+
+    #[test]
+    fn patterns_are_limited_to_a_number_of_cells() {
+        assert_eq!(check_size(4096, 4096, 1, 1), Ok(()));
+        assert_eq!(
+            check_size(4097, 4096, 3, 2),
+            Err(PatternError::new(
+                3,
+                2,
+                "the pattern is 4097x4096 cells, more than the 16777216 allowed"
+            ))
+        );
+        assert!(check_size(i64::from(i32::MAX), i64::from(i32::MAX), 1, 1).is_err());
+    }
+
+    #[test]
+    fn patterns_can_be_centred_on_a_larger_board() {
+        let pattern =
+            Pattern::from_live_cells(&[Coordinates { x: 0, y: 0 }, Coordinates { x: 1, y: 1 }])
+                .unwrap();
+
+        let board = pattern.centred_on(6, 4);
+
+        assert_eq!(board.width(), 6);
+        assert_eq!(
+            Pattern::new(board).live_cells(),
+            vec![Coordinates { x: 2, y: 1 }, Coordinates { x: 3, y: 2 }]
+        );
+    }
+
+    #[test]
+    fn centring_on_a_smaller_area_keeps_every_cell() {
+        let pattern =
+            Pattern::from_live_cells(&[Coordinates { x: 0, y: 0 }, Coordinates { x: 5, y: 0 }])
+                .unwrap();
+
+        assert_eq!(
+            pattern.centred_cells(2, 1),
+            vec![Coordinates { x: -2, y: 0 }, Coordinates { x: 3, y: 0 }]
+        );
+        assert_eq!(pattern.centred_on(2, 1).population(), 0);
+    }
+}
@@ -0,0 +1,146 @@
+//! The plaintext `.cells` format, with `!` comment lines and one character
+//! per cell.
+//!
+//! ```text
+//! !Name: Glider
+//! .O.
+//! ..O
+//! OOO
+//! ```
+
+use std::collections::HashSet;
+
+use super::{board_with_live_cells, check_size, Pattern, PatternError};
+use interface::Board;
+use models::*;
+
+pub fn parse(input: &str) -> Result<Pattern, PatternError> {
+    let mut name = None;
+    let mut comments = Vec::new();
+    let mut live_cells = HashSet::new();
+    let mut width = 0;
+    let mut height = 0;
+
+    for (index, line) in input.lines().enumerate() {
+        if height == 0 && line.starts_with('!') {
+            let text = line[1..].trim();
+            if let Some(text) = text.strip_prefix("Name:") {
+                name = Some(text.trim().to_string());
+            } else {
+                comments.push(text.to_string());
+            }
+            continue;
+        }
+
+        let line = line.trim_end();
+        for (x, character) in line.chars().enumerate() {
+            match character {
+                '.' => {}
+                'O' | '*' => {
+                    live_cells.insert(Coordinates {
+                        x: x as i32,
+                        y: height,
+                    });
+                }
+                _ => {
+                    return Err(PatternError::new(
+                        index + 1,
+                        x + 1,
+                        format!("unexpected character '{}'", character),
+                    ))
+                }
+            }
+        }
+        width = width.max(line.chars().count() as i32);
+        height += 1;
+        check_size(width.into(), height.into(), index + 1, 1)?;
+    }
+
+    Ok(Pattern {
+        name,
+        comments,
+        rule: None,
+        board: board_with_live_cells(width, height, live_cells),
+    })
+}
+
+pub fn to_string(pattern: &Pattern) -> String {
+    let mut output = String::new();
+    if let Some(ref name) = pattern.name {
+        output.push_str(&format!("!Name: {}\n", name));
+    }
+    for comment in &pattern.comments {
+        output.push_str(&format!("!{}\n", comment));
+    }
+
+    for row in pattern.board.rows() {
+        let line: String = row
+            .iter()
+            .map(|cell| if cell.cell_state.is_alive() { 'O' } else { '.' })
+            .collect();
+        output.push_str(&line);
+        output.push('\n');
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GLIDER: &str = "!Name: Glider\n\
+                          !The smallest spaceship.\n\
+                          .O.\n\
+                          ..O\n\
+                          OOO\n";
+
+    #[test]
+    fn parses_the_name_comments_and_cells() {
+        let pattern = parse(GLIDER).unwrap();
+
+        assert_eq!(pattern.name, Some(String::from("Glider")));
+        assert_eq!(
+            pattern.comments,
+            vec![String::from("The smallest spaceship.")]
+        );
+        assert_eq!(
+            pattern.live_cells(),
+            vec![
+                Coordinates { x: 1, y: 0 },
+                Coordinates { x: 2, y: 1 },
+                Coordinates { x: 0, y: 2 },
+                Coordinates { x: 1, y: 2 },
+                Coordinates { x: 2, y: 2 },
+            ]
+        );
+    }
+
+    #[test]
+    fn short_and_blank_lines_are_padded_with_dead_cells() {
+        let pattern = parse("O\n\n..O\n").unwrap();
+
+        assert_eq!(pattern.board.width(), 3);
+        assert_eq!(pattern.board.height(), 3);
+    }
+
+    #[test]
+    fn writes_what_it_reads() {
+        assert_eq!(to_string(&parse(GLIDER).unwrap()), GLIDER);
+    }
+
+    #[test]
+    fn rejects_patterns_with_too_many_cells() {
+        let wide = ".".repeat(5000);
+        let input = format!("{}\n{}", wide, "\n".repeat(4000));
+
+        assert_eq!(parse(&input).unwrap_err().line, 3356);
+    }
+
+    #[test]
+    fn reports_where_the_cells_are_wrong() {
+        assert_eq!(
+            parse("!Comment\n.O.\n.X.\n"),
+            Err(PatternError::new(3, 2, "unexpected character 'X'"))
+        );
+    }
+}
@@ -0,0 +1,431 @@
+//! The Run Length Encoded pattern format, as used by Golly and the LifeWiki.
+//!
+//! ```text
+//! #N Glider
+//! #C The smallest spaceship.
+//! x = 3, y = 3, rule = B3/S23
+//! bo$2bo$3o!
+//! ```
+
+use std::collections::HashSet;
+
+use super::{board_with_live_cells, check_size, Pattern, PatternError};
+use interface::Board;
+use models::*;
+use rule::Rule;
+
+/// Lines of the encoded body are wrapped so that none is longer than this.
+pub const LINE_LENGTH: usize = 70;
+
+struct Header {
+    width: i32,
+    height: i32,
+    rule: Option<Rule>,
+}
+
+pub fn parse(input: &str) -> Result<Pattern, PatternError> {
+    let mut name = None;
+    let mut comments = Vec::new();
+    let mut header = None;
+    let mut body = Body::new();
+    let mut last_line = 0;
+
+    for (index, line) in input.lines().enumerate() {
+        let number = index + 1;
+        last_line = number;
+        match header {
+            None => {
+                let trimmed = line.trim();
+                if trimmed.is_empty() {
+                    continue;
+                }
+                if trimmed.starts_with('#') {
+                    parse_comment(trimmed, &mut name, &mut comments);
+                    continue;
+                }
+                header = Some(parse_header(line, number)?);
+            }
+            Some(ref header) => {
+                if body.parse_line(line, number, header)? {
+                    break;
+                }
+            }
+        }
+    }
+
+    let header = header
+        .ok_or_else(|| PatternError::new(last_line + 1, 1, "missing 'x = .., y = ..' header"))?;
+    if let Some((line, column)) = body.pending_count {
+        return Err(PatternError::new(
+            line,
+            column,
+            "run count is not followed by a cell",
+        ));
+    }
+
+    Ok(Pattern {
+        name,
+        comments,
+        rule: header.rule,
+        board: board_with_live_cells(header.width, header.height, body.live_cells),
+    })
+}
+
+fn parse_comment(line: &str, name: &mut Option<String>, comments: &mut Vec<String>) {
+    let mut chars = line[1..].chars();
+    let kind = chars.next();
+    let text = chars.as_str().trim().to_string();
+    match kind {
+        Some('N') => *name = Some(text),
+        Some('C') | Some('c') => comments.push(text),
+        _ => {}
+    }
+}
+
+fn parse_header(line: &str, number: usize) -> Result<Header, PatternError> {
+    let mut width = None;
+    let mut height = None;
+    let mut rule = None;
+
+    let mut column = 1;
+    for part in line.split(',') {
+        let part_column = column + part.chars().count() - part.trim_start().chars().count();
+        column += part.chars().count() + 1;
+
+        let mut key_value = part.splitn(2, '=');
+        let key = key_value.next().unwrap_or("").trim();
+        let value = match key_value.next() {
+            Some(value) => value.trim(),
+            None => {
+                return Err(PatternError::new(
+                    number,
+                    part_column,
+                    format!("expected 'key = value', found '{}'", part.trim()),
+                ))
+            }
+        };
+
+        match key {
+            "x" => width = Some(parse_dimension(value, number, part_column)?),
+            "y" => height = Some(parse_dimension(value, number, part_column)?),
+            "rule" => {
+                rule = Some(value.parse::<Rule>().map_err(|e| {
+                    PatternError::new(number, part_column, format!("invalid rule: {}", e))
+                })?)
+            }
+            _ => {}
+        }
+    }
+
+    match (width, height) {
+        (Some(width), Some(height)) => {
+            check_size(width.into(), height.into(), number, 1)?;
+            Ok(Header {
+                width,
+                height,
+                rule,
+            })
+        }
+        _ => Err(PatternError::new(
+            number,
+            1,
+            "header must give both 'x' and 'y'",
+        )),
+    }
+}
+
+fn parse_dimension(value: &str, line: usize, column: usize) -> Result<i32, PatternError> {
+    value
+        .parse::<i32>()
+        .ok()
+        .filter(|&n| n >= 0)
+        .ok_or_else(|| PatternError::new(line, column, format!("invalid size '{}'", value)))
+}
+
+struct Body {
+    live_cells: HashSet<Coordinates>,
+    x: i32,
+    y: i32,
+    count: Option<i32>,
+    pending_count: Option<(usize, usize)>,
+}
+
+impl Body {
+    fn new() -> Body {
+        Body {
+            live_cells: HashSet::new(),
+            x: 0,
+            y: 0,
+            count: None,
+            pending_count: None,
+        }
+    }
+
+    /// Returns true once the terminating `!` has been read.
+    fn parse_line(
+        &mut self,
+        line: &str,
+        number: usize,
+        header: &Header,
+    ) -> Result<bool, PatternError> {
+        for (index, character) in line.chars().enumerate() {
+            let column = index + 1;
+            if let Some(digit) = character.to_digit(10) {
+                let count = self
+                    .count
+                    .unwrap_or(0)
+                    .checked_mul(10)
+                    .and_then(|n| n.checked_add(digit as i32))
+                    .ok_or_else(|| PatternError::new(number, column, "run count is too large"))?;
+                self.count = Some(count);
+                self.pending_count = Some((number, column));
+                continue;
+            }
+            if character.is_whitespace() {
+                continue;
+            }
+
+            let run = self.count.take().unwrap_or(1);
+            self.pending_count = None;
+            let past = |from: i32| {
+                from.checked_add(run)
+                    .ok_or_else(|| PatternError::new(number, column, "the pattern is too large"))
+            };
+            match character {
+                'b' => self.x = past(self.x)?,
+                'o' => {
+                    let end = past(self.x)?;
+                    if self.y >= header.height || end > header.width {
+                        return Err(PatternError::new(
+                            number,
+                            column,
+                            format!(
+                                "cells fall outside the {}x{} size in the header",
+                                header.width, header.height
+                            ),
+                        ));
+                    }
+                    for x in self.x..end {
+                        self.live_cells.insert(Coordinates { x, y: self.y });
+                    }
+                    self.x = end;
+                }
+                '$' => {
+                    self.x = 0;
+                    self.y = past(self.y)?;
+                }
+                '!' => return Ok(true),
+                _ => {
+                    return Err(PatternError::new(
+                        number,
+                        column,
+                        format!("unexpected character '{}'", character),
+                    ))
+                }
+            }
+        }
+        Ok(false)
+    }
+}
+
+pub fn to_string(pattern: &Pattern) -> String {
+    let mut output = String::new();
+    if let Some(ref name) = pattern.name {
+        output.push_str(&format!("#N {}\n", name));
+    }
+    for comment in &pattern.comments {
+        output.push_str(&format!("#C {}\n", comment));
+    }
+
+    output.push_str(&format!(
+        "x = {}, y = {}",
+        pattern.board.width(),
+        pattern.board.height()
+    ));
+    if let Some(rule) = pattern.rule {
+        output.push_str(&format!(", rule = {}", rule));
+    }
+    output.push('\n');
+
+    let mut line = String::new();
+    for token in tokens(pattern) {
+        if line.len() + token.len() > LINE_LENGTH {
+            output.push_str(&line);
+            output.push('\n');
+            line.clear();
+        }
+        line.push_str(&token);
+    }
+    output.push_str(&line);
+    output.push('\n');
+    output
+}
+
+/// The runs making up the body of the pattern, ending with `!`.
+fn tokens(pattern: &Pattern) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut pending_rows = 0;
+
+    for row in pattern.board.rows() {
+        let mut runs: Vec<(bool, usize)> = Vec::new();
+        for cell in row {
+            let alive = cell.cell_state.is_alive();
+            match runs.last_mut() {
+                Some(&mut (state, ref mut length)) if state == alive => *length += 1,
+                _ => runs.push((alive, 1)),
+            }
+        }
+        if let Some(&(false, _)) = runs.last() {
+            runs.pop();
+        }
+
+        if runs.is_empty() {
+            pending_rows += 1;
+            continue;
+        }
+        if !tokens.is_empty() {
+            tokens.push(run(pending_rows + 1, '$'));
+        } else if pending_rows > 0 {
+            tokens.push(run(pending_rows, '$'));
+        }
+        pending_rows = 0;
+
+        for (alive, length) in runs {
+            tokens.push(run(length, if alive { 'o' } else { 'b' }));
+        }
+    }
+
+    tokens.push(String::from("!"));
+    tokens
+}
+
+fn run(length: usize, tag: char) -> String {
+    if length == 1 {
+        tag.to_string()
+    } else {
+        format!("{}{}", length, tag)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use board::grid::GridBoard;
+
+    const GLIDER: &str = "#N Glider\n\
+                          #C The smallest spaceship.\n\
+                          x = 3, y = 3, rule = B3/S23\n\
+                          bo$2bo$3o!\n";
+
+    fn alive_at(pattern: &Pattern, x: i32, y: i32) -> bool {
+        pattern.board.at(Coordinates { x, y }).map(|c| c.cell_state) == Some(CellState::Alive)
+    }
+
+    #[test]
+    fn parses_the_header_and_comments() {
+        let pattern = parse(GLIDER).unwrap();
+
+        assert_eq!(pattern.name, Some(String::from("Glider")));
+        assert_eq!(
+            pattern.comments,
+            vec![String::from("The smallest spaceship.")]
+        );
+        assert_eq!(pattern.rule, Some(Rule::conway()));
+        assert_eq!(pattern.board.width(), 3);
+        assert_eq!(pattern.board.height(), 3);
+    }
+
+    #[test]
+    fn parses_the_cells() {
+        let pattern = parse(GLIDER).unwrap();
+
+        assert!(alive_at(&pattern, 1, 0));
+        assert!(alive_at(&pattern, 2, 1));
+        assert!(alive_at(&pattern, 0, 2));
+        assert!(!alive_at(&pattern, 0, 0));
+    }
+
+    #[test]
+    fn the_body_may_span_several_lines() {
+        let pattern = parse("x = 4, y = 3\n2o$\n\n$b\n3o!").unwrap();
+
+        assert!(alive_at(&pattern, 1, 0));
+        assert!(alive_at(&pattern, 3, 2));
+        assert!(!alive_at(&pattern, 0, 2));
+    }
+
+    #[test]
+    fn writes_what_it_reads() {
+        assert_eq!(to_string(&parse(GLIDER).unwrap()), GLIDER);
+    }
+
+    #[test]
+    fn blank_rows_are_merged() {
+        let pattern = parse("x = 2, y = 4\no3$bo!").unwrap();
+
+        assert_eq!(to_string(&pattern), "x = 2, y = 4\no3$bo!\n");
+    }
+
+    #[test]
+    fn long_bodies_are_wrapped() {
+        let pattern = Pattern::new(GridBoard::diagonal(60, 60));
+
+        let output = to_string(&pattern);
+
+        assert!(output.lines().all(|line| line.len() <= LINE_LENGTH));
+        assert_eq!(parse(&output).unwrap(), pattern);
+    }
+
+    #[test]
+    fn reports_where_the_header_is_wrong() {
+        assert_eq!(
+            parse("#C comment\nx = 3, y = three\nbo!"),
+            Err(PatternError::new(2, 8, "invalid size 'three'"))
+        );
+        assert_eq!(
+            parse("x = 3, y = 3, rule = B9/S\nbo!").unwrap_err().column,
+            15
+        );
+    }
+
+    #[test]
+    fn reports_where_the_body_is_wrong() {
+        assert_eq!(
+            parse("x = 3, y = 3\nbo$\n2bq!"),
+            Err(PatternError::new(3, 3, "unexpected character 'q'"))
+        );
+        assert_eq!(
+            parse("x = 2, y = 1\n3o!").unwrap_err(),
+            PatternError::new(2, 2, "cells fall outside the 2x1 size in the header")
+        );
+    }
+
+    #[test]
+    fn rejects_runs_that_go_past_the_largest_coordinate() {
+        assert_eq!(
+            parse("x = 1, y = 1\n2147483647b2b!"),
+            Err(PatternError::new(2, 13, "the pattern is too large"))
+        );
+        assert_eq!(
+            parse("x = 1, y = 1\n2147483647$$!"),
+            Err(PatternError::new(2, 12, "the pattern is too large"))
+        );
+    }
+
+    #[test]
+    fn rejects_headers_too_large_to_hold() {
+        assert_eq!(
+            parse("x = 1000000000, y = 1000000000\no!"),
+            Err(PatternError::new(
+                1,
+                1,
+                "the pattern is 1000000000x1000000000 cells, more than the 16777216 allowed"
+            ))
+        );
+    }
+
+    #[test]
+    fn requires_a_header() {
+        assert_eq!(parse("#N Nothing\n").unwrap_err().line, 2);
+    }
+}
@@ -4,28 +4,41 @@ use termion::{cursor, color};
 
 use models::*;
 use interface::{Board, Renderer};
+use theme::Theme;
+use board::grid::GridBoard;
+use board::sparse::SparseBoard;
 
 pub struct StringRenderer {
-    stdout: Box<RefCell<Write>>,
+    stdout: Box<RefCell<dyn Write>>,
     width: u16,
     height: u16,
     transformer: StringTransformer,
 }
 
 impl<T: Board> Renderer<T> for StringRenderer {
-    fn render(&self, board: &T) {
-        let lines = self.lines(board);
+    fn coordinates_at(&self, board: &T, column: u16, line: u16) -> Option<Coordinates> {
+        if column == 0 || line == 0 || column > self.width || line > self.height {
+            return None;
+        }
+        location_at(board, column as usize - 1, line as usize - 1)
+    }
 
-        let mut writer = self.stdout.borrow_mut();
-        write!(&mut writer, "{}{}", cursor::Goto(1, 1), cursor::Hide,).expect("Couldn't write");
+    fn render(&self, board: &T) {
+        write_lines(&self.stdout, self.lines(board));
+    }
+}
 
-        writer.flush().unwrap();
-        for line in lines {
-            write!(&mut writer, "{}", line).expect("Couldn't write row");
-        }
+/// Draws lines from the top left corner of the terminal.
+fn write_lines(stdout: &RefCell<dyn Write>, lines: Vec<String>) {
+    let mut writer = stdout.borrow_mut();
+    write!(&mut writer, "{}{}", cursor::Goto(1, 1), cursor::Hide,).expect("Couldn't write");
 
-        writer.flush().unwrap();
+    writer.flush().unwrap();
+    for line in lines {
+        write!(&mut writer, "{}", line).expect("Couldn't write row");
     }
+
+    writer.flush().unwrap();
 }
 
 impl StringRenderer {
@@ -38,22 +51,272 @@ impl StringRenderer {
         }
     }
 
+    /// Draws cells in the glyphs and colours of a theme.
+    pub fn with_theme(self, theme: &Theme) -> StringRenderer {
+        StringRenderer {
+            transformer: StringTransformer::with_theme(theme),
+            ..self
+        }
+    }
+
     fn lines<T: Board>(&self, board: &T) -> Vec<String> {
-        board
-            .rows()
+        clipped(board, self.width, self.height)
             .into_iter()
-            .take(self.height as usize)
-            .map(|row| {
-                row.into_iter()
-                    .take(self.width as usize)
-                    .map(|x| Some(*x))
-                    .collect()
-            })
+            .map(|row| row.iter().map(|x| Some(*x)).collect::<Vec<_>>())
             .map(|x| self.transformer.row_to_string(&x))
             .collect()
     }
 }
 
+/// The location of the cell in column `x` and row `y` of a board's rows.
+/// Boards that know their size are asked for the cell directly, so that
+/// ones which build their rows lazily don't have to.
+fn location_at<T: Board>(board: &T, x: usize, y: usize) -> Option<Coordinates> {
+    match board.size() {
+        Some((width, height)) => {
+            if x >= width.max(0) as usize || y >= height.max(0) as usize {
+                return None;
+            }
+            board
+                .at(Coordinates {
+                    x: x as i32,
+                    y: y as i32,
+                })
+                .map(|cell| cell.location)
+        }
+        None => board
+            .rows()
+            .get(y)
+            .and_then(|row| row.get(x))
+            .map(|cell| cell.location),
+    }
+}
+
+/// The rows of a board, cut down to at most `width` cells across and
+/// `height` down.
+fn clipped<T: Board>(board: &T, width: u16, height: u16) -> Vec<&[Cell]> {
+    board
+        .rows()
+        .iter()
+        .take(height as usize)
+        .map(|row| &row[..row.len().min(width as usize)])
+        .collect()
+}
+
+/// The braille dot for each cell of a 2x4 block, by row and then column.
+const BRAILLE_DOTS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+const BRAILLE_BLANK: u32 = 0x2800;
+
+/// Renders a block of 2x4 cells as each braille character, showing eight
+/// times as many cells as a `StringRenderer` in the same space. Characters
+/// holding a cell that was just born are green, and otherwise those holding
+/// a cell that just died are red.
+///
+/// The width and height are in cells, and the board is clipped to them.
+///
+/// Terminals only report which character the mouse is over, not which of
+/// its eight dots, so there's no telling which cell was clicked. Drawing
+/// with the mouse needs the `StringRenderer`, and this renderer leaves
+/// `coordinates_at` finding nothing.
+pub struct BrailleRenderer {
+    stdout: Box<RefCell<dyn Write>>,
+    width: u16,
+    height: u16,
+}
+
+impl<T: Board> Renderer<T> for BrailleRenderer {
+    fn render(&self, board: &T) {
+        write_lines(&self.stdout, self.lines(board));
+    }
+}
+
+impl BrailleRenderer {
+    pub fn new(stdout: Stdout, width: u16, height: u16) -> BrailleRenderer {
+        BrailleRenderer {
+            stdout: Box::new(RefCell::new(stdout)),
+            width,
+            height,
+        }
+    }
+
+    fn lines<T: Board>(&self, board: &T) -> Vec<String> {
+        clipped(board, self.width, self.height)
+            .chunks(4)
+            .map(braille_line)
+            .collect()
+    }
+}
+
+/// Draws up to four rows of cells as one line of braille characters.
+fn braille_line(rows: &[&[Cell]]) -> String {
+    let columns = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    let mut line = String::new();
+    for left in (0..columns).step_by(2) {
+        let mut dots = 0;
+        let (mut born, mut died) = (false, false);
+        for (y, row) in rows.iter().enumerate() {
+            for (x, dot) in BRAILLE_DOTS[y].iter().enumerate() {
+                match row.get(left + x).map(|cell| cell.cell_state) {
+                    Some(CellState::Alive) => dots |= dot,
+                    Some(CellState::Born) => {
+                        dots |= dot;
+                        born = true;
+                    }
+                    Some(CellState::Died) => died = true,
+                    Some(CellState::Dead) | None => {}
+                }
+            }
+        }
+        let glyph = ::std::char::from_u32(BRAILLE_BLANK + dots).unwrap_or(' ');
+        let colour = if born {
+            Some(color::Fg(color::Green).to_string())
+        } else if died {
+            Some(color::Fg(color::Red).to_string())
+        } else {
+            None
+        };
+        match colour {
+            Some(colour) => line.push_str(&format!(
+                "{}{}{}",
+                colour,
+                glyph,
+                color::Fg(color::Reset)
+            )),
+            None => line.push(glyph),
+        }
+    }
+    line
+}
+
+const UPPER_HALF: char = '\u{2580}';
+const LOWER_HALF: char = '\u{2584}';
+const FULL_BLOCK: char = '\u{2588}';
+
+/// Renders two cells, one above the other, as each character by colouring
+/// the halves of block characters in 24-bit colour. Dead cells are left in
+/// the terminal's background colour.
+///
+/// The width and height are in cells, and the board is clipped to them.
+///
+/// Like the `BrailleRenderer`, there's no telling which half of a character
+/// the mouse is over, so this renderer leaves `coordinates_at` finding
+/// nothing and drawing with the mouse to the `StringRenderer`.
+pub struct HalfBlockRenderer {
+    stdout: Box<RefCell<dyn Write>>,
+    width: u16,
+    height: u16,
+}
+
+impl<T: Board> Renderer<T> for HalfBlockRenderer {
+    fn render(&self, board: &T) {
+        write_lines(&self.stdout, self.lines(board));
+    }
+}
+
+impl HalfBlockRenderer {
+    pub fn new(stdout: Stdout, width: u16, height: u16) -> HalfBlockRenderer {
+        HalfBlockRenderer {
+            stdout: Box::new(RefCell::new(stdout)),
+            width,
+            height,
+        }
+    }
+
+    fn lines<T: Board>(&self, board: &T) -> Vec<String> {
+        clipped(board, self.width, self.height)
+            .chunks(2)
+            .map(half_block_line)
+            .collect()
+    }
+}
+
+/// The colour of a cell in a `HalfBlockRenderer`, or `None` for dead cells.
+fn half_block_colour(cell_state: CellState) -> Option<color::Rgb> {
+    match cell_state {
+        CellState::Alive => Some(color::Rgb(230, 230, 230)),
+        CellState::Born => Some(color::Rgb(80, 220, 100)),
+        CellState::Died => Some(color::Rgb(200, 60, 60)),
+        CellState::Dead => None,
+    }
+}
+
+/// Draws two rows of cells as one line of half blocks.
+fn half_block_line(rows: &[&[Cell]]) -> String {
+    let colour_at = |y: usize, x: usize| {
+        rows.get(y)
+            .and_then(|row| row.get(x))
+            .and_then(|cell| half_block_colour(cell.cell_state))
+    };
+    let columns = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    let mut line = String::new();
+    for x in 0..columns {
+        let glyph = match (colour_at(0, x), colour_at(1, x)) {
+            (None, None) => String::from(" "),
+            (Some(top), None) => format!("{}{}", color::Fg(top), UPPER_HALF),
+            (None, Some(bottom)) => format!("{}{}", color::Fg(bottom), LOWER_HALF),
+            (Some(top), Some(bottom)) if top == bottom => {
+                format!("{}{}", color::Fg(top), FULL_BLOCK)
+            }
+            (Some(top), Some(bottom)) => format!(
+                "{}{}{}{}",
+                color::Fg(top),
+                color::Bg(bottom),
+                UPPER_HALF,
+                color::Bg(color::Reset)
+            ),
+        };
+        line.push_str(&glyph);
+        if glyph != " " {
+            line.push_str(&color::Fg(color::Reset).to_string());
+        }
+    }
+    line
+}
+
+/// Renders nothing, for running the game without a terminal.
+pub struct NullRenderer;
+
+impl<T: Board> Renderer<T> for NullRenderer {
+    fn render(&self, _board: &T) {}
+}
+
+/// Renders a fixed window onto an unbounded `SparseBoard` using a renderer
+/// for `GridBoard`s.
+pub struct ViewportRenderer<R: Renderer<GridBoard>> {
+    renderer: R,
+    origin: Coordinates,
+    width: i32,
+    height: i32,
+}
+
+impl<R: Renderer<GridBoard>> ViewportRenderer<R> {
+    pub fn new(renderer: R, origin: Coordinates, width: i32, height: i32) -> ViewportRenderer<R> {
+        ViewportRenderer {
+            renderer,
+            origin,
+            width,
+            height,
+        }
+    }
+}
+
+impl<R: Renderer<GridBoard>> Renderer<SparseBoard> for ViewportRenderer<R> {
+    fn render(&self, board: &SparseBoard) {
+        self.renderer
+            .render(&board.viewport(self.origin, self.width, self.height));
+    }
+
+    fn coordinates_at(&self, board: &SparseBoard, column: u16, line: u16) -> Option<Coordinates> {
+        let viewport = board.viewport(self.origin, self.width, self.height);
+        self.renderer
+            .coordinates_at(&viewport, column, line)
+            .map(|c| Coordinates {
+                x: c.x + self.origin.x,
+                y: c.y + self.origin.y,
+            })
+    }
+}
+
 struct StringTransformer {
     alive: String,
     dead: String,
@@ -64,16 +327,20 @@ struct StringTransformer {
 
 impl StringTransformer {
     fn new() -> StringTransformer {
+        StringTransformer::with_theme(&Theme::default())
+    }
+
+    fn with_theme(theme: &Theme) -> StringTransformer {
         StringTransformer {
-            alive: String::from("o"),
-            dead: String::from(" "),
-            born: format!("{}O{}", color::Fg(color::Green), color::Fg(color::Reset)),
-            died: format!("{}x{}", color::Fg(color::Red), color::Fg(color::Reset)),
-            none: String::from("x"),
+            alive: theme.alive.to_terminal_string(),
+            dead: theme.dead.to_terminal_string(),
+            born: theme.born.to_terminal_string(),
+            died: theme.died.to_terminal_string(),
+            none: theme.none.to_terminal_string(),
         }
     }
 
-    fn row_to_string(&self, cells: &Vec<Option<Cell>>) -> String {
+    fn row_to_string(&self, cells: &[Option<Cell>]) -> String {
         cells.iter().map(|x| self.cell_to_str(x)).collect()
     }
 
@@ -99,6 +366,155 @@ impl StringTransformer {
 mod tests {
     use models::CellState::*;
     use super::*;
+    use std::io::stdout;
+
+    #[test]
+    fn terminal_positions_map_to_the_cells_drawn_there() {
+        let board = GridBoard::diagonal(10, 10);
+        let renderer = StringRenderer::new(stdout(), 5, 4);
+
+        assert_eq!(
+            renderer.coordinates_at(&board, 1, 1),
+            Some(Coordinates { x: 0, y: 0 })
+        );
+        assert_eq!(
+            renderer.coordinates_at(&board, 5, 2),
+            Some(Coordinates { x: 4, y: 1 })
+        );
+        assert_eq!(renderer.coordinates_at(&board, 6, 2), None);
+        assert_eq!(renderer.coordinates_at(&board, 0, 2), None);
+    }
+
+    fn cells(states: &[&[CellState]]) -> Vec<Vec<Cell>> {
+        states
+            .iter()
+            .enumerate()
+            .map(|(y, row)| {
+                row.iter()
+                    .enumerate()
+                    .map(|(x, &cell_state)| Cell {
+                        cell_state,
+                        location: Coordinates {
+                            x: x as i32,
+                            y: y as i32,
+                        },
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn braille_packs_two_by_four_cells_into_each_character() {
+        let board = GridBoard::from(cells(&[
+            &[Alive, Dead, Alive, Alive],
+            &[Dead, Alive, Dead, Dead],
+            &[Dead, Dead, Dead, Dead],
+            &[Alive, Alive, Dead, Dead],
+            &[Dead, Dead, Dead, Alive],
+        ]));
+        let renderer = BrailleRenderer::new(stdout(), 4, 5);
+
+        assert_eq!(
+            renderer.lines(&board),
+            vec!["\u{28d1}\u{2809}", "\u{2800}\u{2808}"]
+        );
+    }
+
+    #[test]
+    fn braille_is_clipped_to_the_width_and_height() {
+        let board = GridBoard::all_alive(10);
+        let renderer = BrailleRenderer::new(stdout(), 3, 2);
+
+        assert_eq!(renderer.lines(&board), vec!["\u{281b}\u{2803}"]);
+    }
+
+    #[test]
+    fn braille_cant_tell_which_cell_the_mouse_is_over() {
+        let board = GridBoard::all_alive(10);
+        let renderer = BrailleRenderer::new(stdout(), 10, 10);
+
+        assert_eq!(renderer.coordinates_at(&board, 1, 1), None);
+        assert_eq!(renderer.coordinates_at(&board, 2, 2), None);
+    }
+
+    #[test]
+    fn braille_colours_characters_by_their_births_and_deaths() {
+        let board = GridBoard::from(cells(&[&[Born, Died, Died, Dead, Alive, Dead]]));
+        let renderer = BrailleRenderer::new(stdout(), 6, 4);
+
+        assert_eq!(
+            renderer.lines(&board),
+            vec![format!(
+                "{}\u{2801}{}{}\u{2800}{}\u{2801}",
+                color::Fg(color::Green),
+                color::Fg(color::Reset),
+                color::Fg(color::Red),
+                color::Fg(color::Reset)
+            )]
+        );
+    }
+
+    #[test]
+    fn half_blocks_draw_two_cells_in_each_character() {
+        let board = GridBoard::from(cells(&[
+            &[Alive, Dead, Alive, Dead],
+            &[Dead, Alive, Alive, Dead],
+            &[Alive, Dead, Dead, Dead],
+        ]));
+        let renderer = HalfBlockRenderer::new(stdout(), 4, 3);
+        let white = color::Fg(color::Rgb(230, 230, 230));
+        let reset = color::Fg(color::Reset);
+
+        assert_eq!(
+            renderer.lines(&board),
+            vec![
+                format!(
+                    "{}\u{2580}{}{}\u{2584}{}{}\u{2588}{} ",
+                    white, reset, white, reset, white, reset
+                ),
+                format!("{}\u{2580}{}   ", white, reset),
+            ]
+        );
+    }
+
+    #[test]
+    fn half_blocks_colour_each_half_separately() {
+        let board = GridBoard::from(cells(&[&[Born], &[Died]]));
+        let renderer = HalfBlockRenderer::new(stdout(), 1, 2);
+
+        assert_eq!(
+            renderer.lines(&board),
+            vec![format!(
+                "{}{}\u{2580}{}{}",
+                color::Fg(color::Rgb(80, 220, 100)),
+                color::Bg(color::Rgb(200, 60, 60)),
+                color::Bg(color::Reset),
+                color::Fg(color::Reset)
+            )]
+        );
+    }
+
+    #[test]
+    fn half_blocks_are_clipped_like_strings() {
+        let board = GridBoard::all_alive(10);
+        let renderer = HalfBlockRenderer::new(stdout(), 3, 3);
+
+        let lines = renderer.lines(&board);
+
+        assert_eq!(lines.len(), 2);
+        assert!(lines[1].ends_with(&format!("\u{2580}{}", color::Fg(color::Reset))));
+        assert_eq!(lines[0].matches('\u{2588}').count(), 3);
+    }
+
+    #[test]
+    fn half_blocks_cant_tell_which_cell_the_mouse_is_over() {
+        let board = GridBoard::all_alive(10);
+        let renderer = HalfBlockRenderer::new(stdout(), 10, 10);
+
+        assert_eq!(renderer.coordinates_at(&board, 1, 1), None);
+        assert_eq!(renderer.coordinates_at(&board, 3, 2), None);
+    }
 
     #[test]
     fn born_cells_are_green() {
@@ -145,6 +561,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn themes_change_how_cells_are_drawn() {
+        let cells = vec![
+            Some(Cell {
+                cell_state: Born,
+                location: Coordinates { x: 0, y: 0 },
+            }),
+            Some(Cell {
+                cell_state: Died,
+                location: Coordinates { x: 1, y: 0 },
+            }),
+            None,
+        ];
+        let transformer = StringTransformer::with_theme(&Theme::monochrome());
+
+        assert_eq!(transformer.row_to_string(&cells), "O.x");
+    }
+
     #[test]
     fn dead_cells_are_blank() {
         let location = Coordinates { x: 0, y: 0 };
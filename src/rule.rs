@@ -0,0 +1,238 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// A Life-like cellular automaton rule, described by the neighbour counts
+/// that cause a dead cell to be born and a living cell to survive.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Rule {
+    birth: u16,
+    survival: u16,
+}
+
+impl Rule {
+    pub fn new(birth: &[usize], survival: &[usize]) -> Rule {
+        Rule {
+            birth: mask_of(birth),
+            survival: mask_of(survival),
+        }
+    }
+
+    /// Conway's Game of Life, B3/S23.
+    pub fn conway() -> Rule {
+        Rule::new(&[3], &[2, 3])
+    }
+
+    pub fn is_born(&self, living_neighbours: usize) -> bool {
+        living_neighbours <= 8 && self.birth & (1 << living_neighbours) != 0
+    }
+
+    pub fn survives(&self, living_neighbours: usize) -> bool {
+        living_neighbours <= 8 && self.survival & (1 << living_neighbours) != 0
+    }
+
+    pub fn birth_counts(&self) -> Vec<usize> {
+        counts_of(self.birth)
+    }
+
+    pub fn survival_counts(&self) -> Vec<usize> {
+        counts_of(self.survival)
+    }
+}
+
+impl Default for Rule {
+    fn default() -> Rule {
+        Rule::conway()
+    }
+}
+
+fn mask_of(counts: &[usize]) -> u16 {
+    counts
+        .iter()
+        .filter(|&&n| n <= 8)
+        .fold(0, |mask, &n| mask | (1 << n))
+}
+
+fn counts_of(mask: u16) -> Vec<usize> {
+    (0..9).filter(|&n| mask & (1 << n) != 0).collect()
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "B")?;
+        for n in self.birth_counts() {
+            write!(f, "{}", n)?;
+        }
+        write!(f, "/S")?;
+        for n in self.survival_counts() {
+            write!(f, "{}", n)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses rulestrings in B/S notation ("B3/S23", "B36/S23", "B2/S") as well
+/// as the legacy survival/birth notation ("23/3").
+impl FromStr for Rule {
+    type Err = ParseRuleError;
+
+    fn from_str(s: &str) -> Result<Rule, ParseRuleError> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(ParseRuleError::Empty);
+        }
+
+        let sections: Vec<&str> = s.split('/').collect();
+        if sections.len() != 2 {
+            return Err(ParseRuleError::Sections(sections.len()));
+        }
+
+        let prefixes: Vec<Option<char>> = sections.iter().map(|s| prefix_of(s)).collect();
+        match (prefixes[0], prefixes[1]) {
+            (None, None) => {
+                let survival = parse_counts(sections[0], 0)?;
+                let birth = parse_counts(sections[1], sections[0].len() + 1)?;
+                Ok(Rule { birth, survival })
+            }
+            (Some(first), Some(second)) if first == second => {
+                Err(ParseRuleError::RepeatedSection(first))
+            }
+            (Some(first), Some(_)) => {
+                let a = parse_counts(&sections[0][1..], 1)?;
+                let b = parse_counts(&sections[1][1..], sections[0].len() + 2)?;
+                if first == 'B' {
+                    Ok(Rule {
+                        birth: a,
+                        survival: b,
+                    })
+                } else {
+                    Ok(Rule {
+                        birth: b,
+                        survival: a,
+                    })
+                }
+            }
+            _ => Err(ParseRuleError::MixedNotation),
+        }
+    }
+}
+
+fn prefix_of(section: &str) -> Option<char> {
+    section
+        .chars()
+        .next()
+        .map(|c| c.to_ascii_uppercase())
+        .and_then(|c| if c == 'B' || c == 'S' { Some(c) } else { None })
+}
+
+fn parse_counts(digits: &str, offset: usize) -> Result<u16, ParseRuleError> {
+    digits
+        .chars()
+        .enumerate()
+        .try_fold(0, |mask, (i, character)| match character.to_digit(10) {
+            Some(n) if n <= 8 => Ok(mask | (1 << n)),
+            _ => Err(ParseRuleError::InvalidCharacter {
+                character,
+                position: offset + i,
+            }),
+        })
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ParseRuleError {
+    Empty,
+    Sections(usize),
+    MixedNotation,
+    RepeatedSection(char),
+    InvalidCharacter { character: char, position: usize },
+}
+
+impl fmt::Display for ParseRuleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ParseRuleError::Empty => write!(f, "rulestring is empty"),
+            ParseRuleError::Sections(n) => write!(
+                f,
+                "rulestring must have two sections separated by '/', found {}",
+                n
+            ),
+            ParseRuleError::MixedNotation => {
+                write!(f, "rulestring mixes B/S notation with legacy S/B notation")
+            }
+            ParseRuleError::RepeatedSection(c) => {
+                write!(f, "rulestring has two '{}' sections", c)
+            }
+            ParseRuleError::InvalidCharacter {
+                character,
+                position,
+            } => write!(
+                f,
+                "invalid neighbour count '{}' at position {} (expected 0-8)",
+                character, position
+            ),
+        }
+    }
+}
+
+impl Error for ParseRuleError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conway_is_the_default_rule() {
+        assert_eq!("B3/S23".parse(), Ok(Rule::default()));
+    }
+
+    #[test]
+    fn parses_highlife() {
+        let rule: Rule = "B36/S23".parse().unwrap();
+        assert!(rule.is_born(3));
+        assert!(rule.is_born(6));
+        assert!(!rule.is_born(2));
+        assert!(rule.survives(2));
+        assert!(!rule.survives(6));
+    }
+
+    #[test]
+    fn parses_rules_with_an_empty_section() {
+        let seeds: Rule = "B2/S".parse().unwrap();
+        assert_eq!(seeds, Rule::new(&[2], &[]));
+    }
+
+    #[test]
+    fn parses_legacy_notation_as_survival_then_birth() {
+        assert_eq!("23/3".parse(), Ok(Rule::conway()));
+        assert_eq!("23/36".parse(), Ok(Rule::new(&[3, 6], &[2, 3])));
+    }
+
+    #[test]
+    fn parses_sections_in_either_order_and_case() {
+        assert_eq!("s23/b3".parse(), Ok(Rule::conway()));
+    }
+
+    #[test]
+    fn displays_in_bs_notation() {
+        let day_and_night = Rule::new(&[3, 6, 7, 8], &[3, 4, 6, 7, 8]);
+        assert_eq!(day_and_night.to_string(), "B3678/S34678");
+    }
+
+    #[test]
+    fn rejects_malformed_rulestrings() {
+        assert_eq!("".parse::<Rule>(), Err(ParseRuleError::Empty));
+        assert_eq!("B3S23".parse::<Rule>(), Err(ParseRuleError::Sections(1)));
+        assert_eq!("B3/23".parse::<Rule>(), Err(ParseRuleError::MixedNotation));
+        assert_eq!(
+            "B3/B23".parse::<Rule>(),
+            Err(ParseRuleError::RepeatedSection('B'))
+        );
+        assert_eq!(
+            "B39/S23".parse::<Rule>(),
+            Err(ParseRuleError::InvalidCharacter {
+                character: '9',
+                position: 2,
+            })
+        );
+    }
+}
@@ -0,0 +1,238 @@
+//! Per-generation statistics of a game, for graphing how a soup evolves.
+
+use std::fmt::Write;
+
+use interface::{Board, Control, Observer};
+use models::{BoundingBox, CellState};
+
+/// What a board looked like in one generation.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct GenerationStatistics {
+    pub generation: u32,
+    pub population: usize,
+    /// Cells that came alive since the last generation.
+    pub births: usize,
+    /// Cells that died since the last generation.
+    pub deaths: usize,
+    /// The smallest rectangle holding every living cell, if there are any.
+    pub bounding_box: Option<BoundingBox>,
+    /// How many cells the board has, unless it goes on forever.
+    pub area: Option<usize>,
+}
+
+impl GenerationStatistics {
+    pub fn of<T: Board>(generation: u32, board: &T) -> GenerationStatistics {
+        let (mut births, mut deaths) = (0, 0);
+        for cell in board.changed_cells() {
+            if cell.cell_state == CellState::Born {
+                births += 1;
+            } else {
+                deaths += 1;
+            }
+        }
+        GenerationStatistics {
+            generation,
+            population: board.population(),
+            births,
+            deaths,
+            bounding_box: board.bounding_box(),
+            area: board
+                .size()
+                .map(|(width, height)| width as usize * height as usize),
+        }
+    }
+
+    /// The fraction of the board's cells that are alive, unless the board
+    /// goes on forever.
+    pub fn density(&self) -> Option<f64> {
+        self.area.map(|area| {
+            if area == 0 {
+                0.0
+            } else {
+                self.population as f64 / area as f64
+            }
+        })
+    }
+}
+
+/// Statistics collected over a game, one entry per generation. They can be
+/// recorded by hand or by observing the game.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Statistics {
+    generations: Vec<GenerationStatistics>,
+}
+
+impl Statistics {
+    pub fn new() -> Statistics {
+        Statistics::default()
+    }
+
+    /// Records the board as the next generation.
+    pub fn record<T: Board>(&mut self, board: &T) {
+        let generation = self.generations.len() as u32;
+        self.generations
+            .push(GenerationStatistics::of(generation, board));
+    }
+
+    pub fn generations(&self) -> &[GenerationStatistics] {
+        &self.generations
+    }
+
+    /// One line per generation, after a header line. The bounding box
+    /// columns are left empty for empty boards, and the density for boards
+    /// that go on forever.
+    pub fn to_csv(&self) -> String {
+        let mut output =
+            String::from("generation,population,births,deaths,min_x,min_y,max_x,max_y,density\n");
+        for g in &self.generations {
+            let bounds = match g.bounding_box {
+                Some(b) => format!("{},{},{},{}", b.min.x, b.min.y, b.max.x, b.max.y),
+                None => String::from(",,,"),
+            };
+            let _ = writeln!(
+                output,
+                "{},{},{},{},{},{}",
+                g.generation,
+                g.population,
+                g.births,
+                g.deaths,
+                bounds,
+                g.density().map_or(String::new(), |d| d.to_string())
+            );
+        }
+        output
+    }
+
+    /// An array with an object per generation. Empty boards have a null
+    /// bounding box, and boards that go on forever a null density.
+    pub fn to_json(&self) -> String {
+        let mut output = String::from("[");
+        for (i, g) in self.generations.iter().enumerate() {
+            let bounds = match g.bounding_box {
+                Some(b) => format!(
+                    "{{\"min_x\":{},\"min_y\":{},\"max_x\":{},\"max_y\":{}}}",
+                    b.min.x, b.min.y, b.max.x, b.max.y
+                ),
+                None => String::from("null"),
+            };
+            let _ = write!(
+                output,
+                "{}\n  {{\"generation\":{},\"population\":{},\"births\":{},\"deaths\":{},\
+                 \"bounding_box\":{},\"density\":{}}}",
+                if i == 0 { "" } else { "," },
+                g.generation,
+                g.population,
+                g.births,
+                g.deaths,
+                bounds,
+                g.density().map_or(String::from("null"), |d| d.to_string())
+            );
+        }
+        if !self.generations.is_empty() {
+            output.push('\n');
+        }
+        output.push_str("]\n");
+        output
+    }
+}
+
+impl<T: Board> Observer<T> for Statistics {
+    fn observe(&mut self, generation: u32, board: &T) -> Control {
+        self.generations
+            .push(GenerationStatistics::of(generation, board));
+        Control::Continue
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use board::grid::GridBoard;
+    use board::sparse::SparseBoard;
+    use generator::SimpleGenerator;
+    use interface::Generator;
+    use models::Coordinates;
+
+    fn blinker() -> GridBoard {
+        GridBoard::fill_with(
+            5,
+            4,
+            Box::new(|c| {
+                if c.y == 1 && c.x > 0 && c.x < 4 {
+                    CellState::Alive
+                } else {
+                    CellState::Dead
+                }
+            }),
+        )
+    }
+
+    fn recorded(generations: u32) -> Statistics {
+        let generator = SimpleGenerator::new();
+        let mut statistics = Statistics::new();
+        let mut board = blinker();
+        statistics.record(&board);
+        for _ in 0..generations {
+            board = generator.generate(&board);
+            statistics.record(&board);
+        }
+        statistics
+    }
+
+    #[test]
+    fn records_each_generation() {
+        let statistics = recorded(1);
+        let next = statistics.generations()[1];
+
+        assert_eq!(statistics.generations().len(), 2);
+        assert_eq!(next.generation, 1);
+        assert_eq!((next.population, next.births, next.deaths), (3, 2, 2));
+        assert_eq!(
+            next.bounding_box,
+            Some(BoundingBox {
+                min: Coordinates { x: 2, y: 0 },
+                max: Coordinates { x: 2, y: 2 },
+            })
+        );
+        assert_eq!(next.area, Some(20));
+        assert_eq!(next.density(), Some(0.15));
+    }
+
+    #[test]
+    fn counts_deaths_anywhere_on_an_unbounded_board() {
+        let lonely = SparseBoard::with_live_cells(vec![Coordinates { x: 5, y: 5 }]);
+        let extinct = SimpleGenerator::new().generate(&lonely);
+
+        let statistics = GenerationStatistics::of(1, &extinct);
+
+        assert_eq!(
+            (statistics.population, statistics.births, statistics.deaths),
+            (0, 0, 1)
+        );
+        assert_eq!(statistics.area, None);
+        assert_eq!(statistics.density(), None);
+    }
+
+    #[test]
+    fn exports_csv() {
+        assert_eq!(
+            recorded(1).to_csv(),
+            "generation,population,births,deaths,min_x,min_y,max_x,max_y,density\n\
+             0,3,0,0,1,1,3,1,0.15\n\
+             1,3,2,2,2,0,2,2,0.15\n"
+        );
+    }
+
+    #[test]
+    fn exports_json() {
+        let mut statistics = Statistics::new();
+        statistics.record(&GridBoard::fill_with(2, 2, Box::new(|_| CellState::Dead)));
+
+        assert_eq!(
+            statistics.to_json(),
+            "[\n  {\"generation\":0,\"population\":0,\"births\":0,\"deaths\":0,\
+             \"bounding_box\":null,\"density\":0}\n]\n"
+        );
+        assert_eq!(Statistics::new().to_json(), "[]\n");
+    }
+}
@@ -0,0 +1,433 @@
+//! How a `StringRenderer` draws each kind of cell.
+//!
+//! A theme can be built in code, picked from the presets, or read from a
+//! small file of `key = value` lines, where the keys are the cell states
+//! `alive`, `dead`, `born` and `died`, or `none` for cells off the board:
+//!
+//! ```text
+//! # Start from a preset, then change parts of it.
+//! preset = monochrome
+//! born = "O"
+//! born.fg = #56b4e9
+//! dead.bg = black
+//! ```
+//!
+//! A state on its own sets the glyph, which can be quoted to keep spaces.
+//! `.fg` and `.bg` set the foreground and background colours, as one of the
+//! eight terminal colour names, optionally prefixed with `bright-`, as
+//! `#rrggbb`, or as `default` to leave the terminal's colour alone.
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use termion::color;
+
+use models::CellState;
+
+const COLOUR_NAMES: &[&str] = &[
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+];
+
+/// A terminal colour.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Colour {
+    /// One of the terminal's sixteen colours, where 0 to 7 are black, red,
+    /// green, yellow, blue, magenta, cyan and white, and 8 to 15 are their
+    /// bright versions.
+    Ansi(u8),
+    /// A 24-bit colour.
+    Rgb(u8, u8, u8),
+}
+
+impl Colour {
+    pub const BLACK: Colour = Colour::Ansi(0);
+    pub const RED: Colour = Colour::Ansi(1);
+    pub const GREEN: Colour = Colour::Ansi(2);
+    pub const YELLOW: Colour = Colour::Ansi(3);
+    pub const WHITE: Colour = Colour::Ansi(7);
+
+    fn foreground(self) -> String {
+        match self {
+            Colour::Ansi(value) => color::Fg(color::AnsiValue(value)).to_string(),
+            Colour::Rgb(r, g, b) => color::Fg(color::Rgb(r, g, b)).to_string(),
+        }
+    }
+
+    fn background(self) -> String {
+        match self {
+            Colour::Ansi(value) => color::Bg(color::AnsiValue(value)).to_string(),
+            Colour::Rgb(r, g, b) => color::Bg(color::Rgb(r, g, b)).to_string(),
+        }
+    }
+}
+
+impl FromStr for Colour {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Colour, String> {
+        let lower = s.to_lowercase();
+        if let Some(hex) = lower.strip_prefix('#') {
+            if hex.len() == 6 && hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+                let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).unwrap_or(0);
+                return Ok(Colour::Rgb(channel(0), channel(2), channel(4)));
+            }
+        }
+        let (name, bright) = match lower.strip_prefix("bright-") {
+            Some(name) => (name, 8),
+            None => (lower.as_str(), 0),
+        };
+        COLOUR_NAMES
+            .iter()
+            .position(|&n| n == name)
+            .map(|value| Colour::Ansi(value as u8 + bright))
+            .ok_or_else(|| format!("unknown colour '{}'", s))
+    }
+}
+
+/// How one kind of cell is drawn: a glyph, and the colours to draw it in.
+/// Without colours, the terminal's own are used.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Style {
+    pub glyph: String,
+    pub foreground: Option<Colour>,
+    pub background: Option<Colour>,
+}
+
+impl Style {
+    pub fn new<S: Into<String>>(glyph: S) -> Style {
+        Style {
+            glyph: glyph.into(),
+            foreground: None,
+            background: None,
+        }
+    }
+
+    pub fn with_foreground(self, colour: Colour) -> Style {
+        Style {
+            foreground: Some(colour),
+            ..self
+        }
+    }
+
+    pub fn with_background(self, colour: Colour) -> Style {
+        Style {
+            background: Some(colour),
+            ..self
+        }
+    }
+
+    /// The glyph wrapped in the escape codes that colour it.
+    pub fn to_terminal_string(&self) -> String {
+        let mut output = String::new();
+        if let Some(colour) = self.foreground {
+            output.push_str(&colour.foreground());
+        }
+        if let Some(colour) = self.background {
+            output.push_str(&colour.background());
+        }
+        output.push_str(&self.glyph);
+        if self.foreground.is_some() {
+            output.push_str(&color::Fg(color::Reset).to_string());
+        }
+        if self.background.is_some() {
+            output.push_str(&color::Bg(color::Reset).to_string());
+        }
+        output
+    }
+}
+
+/// The styles of every kind of cell.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Theme {
+    pub alive: Style,
+    pub dead: Style,
+    pub born: Style,
+    pub died: Style,
+    /// For places with no cell, off the edge of the board.
+    pub none: Style,
+}
+
+impl Default for Theme {
+    fn default() -> Theme {
+        Theme::classic()
+    }
+}
+
+impl Theme {
+    /// Plain living cells, with green births and red deaths.
+    pub fn classic() -> Theme {
+        Theme {
+            alive: Style::new("o"),
+            dead: Style::new(" "),
+            born: Style::new("O").with_foreground(Colour::GREEN),
+            died: Style::new("x").with_foreground(Colour::RED),
+            none: Style::new("x"),
+        }
+    }
+
+    /// No colours at all, telling births and deaths apart by their glyphs.
+    pub fn monochrome() -> Theme {
+        Theme {
+            alive: Style::new("o"),
+            dead: Style::new(" "),
+            born: Style::new("O"),
+            died: Style::new("."),
+            none: Style::new("x"),
+        }
+    }
+
+    /// Solid blocks in bright colours on black.
+    pub fn high_contrast() -> Theme {
+        let on_black = |glyph: &str, colour: Colour| {
+            Style::new(glyph)
+                .with_foreground(colour)
+                .with_background(Colour::BLACK)
+        };
+        Theme {
+            alive: on_black("\u{2588}", Colour::Ansi(15)),
+            dead: on_black(" ", Colour::Ansi(15)),
+            born: on_black("\u{2588}", Colour::Ansi(11)),
+            died: on_black("x", Colour::Ansi(9)),
+            none: on_black("/", Colour::Ansi(8)),
+        }
+    }
+
+    /// Births in sky blue and deaths in orange, which can be told apart with
+    /// any common kind of colour blindness.
+    pub fn colour_blind_safe() -> Theme {
+        Theme {
+            alive: Style::new("o"),
+            dead: Style::new(" "),
+            born: Style::new("O").with_foreground(Colour::Rgb(86, 180, 233)),
+            died: Style::new("x").with_foreground(Colour::Rgb(230, 159, 0)),
+            none: Style::new("x"),
+        }
+    }
+
+    /// A built-in theme by name: classic, monochrome, high-contrast or
+    /// colour-blind.
+    pub fn preset(name: &str) -> Option<Theme> {
+        match name {
+            "classic" => Some(Theme::classic()),
+            "monochrome" => Some(Theme::monochrome()),
+            "high-contrast" => Some(Theme::high_contrast()),
+            "colour-blind" | "color-blind" => Some(Theme::colour_blind_safe()),
+            _ => None,
+        }
+    }
+
+    pub fn with_style(mut self, cell_state: Option<CellState>, style: Style) -> Theme {
+        *self.style_mut(cell_state) = style;
+        self
+    }
+
+    /// The style of a kind of cell, where `None` is for places with no cell.
+    pub fn style(&self, cell_state: Option<CellState>) -> &Style {
+        match cell_state {
+            Some(CellState::Alive) => &self.alive,
+            Some(CellState::Dead) => &self.dead,
+            Some(CellState::Born) => &self.born,
+            Some(CellState::Died) => &self.died,
+            None => &self.none,
+        }
+    }
+
+    fn style_mut(&mut self, cell_state: Option<CellState>) -> &mut Style {
+        match cell_state {
+            Some(CellState::Alive) => &mut self.alive,
+            Some(CellState::Dead) => &mut self.dead,
+            Some(CellState::Born) => &mut self.born,
+            Some(CellState::Died) => &mut self.died,
+            None => &mut self.none,
+        }
+    }
+
+    /// Reads a theme file, starting from the classic theme.
+    pub fn parse(input: &str) -> Result<Theme, ThemeError> {
+        let mut theme = Theme::classic();
+        for (index, line) in input.lines().enumerate() {
+            let error = |message: String| ThemeError {
+                line: index + 1,
+                message,
+            };
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = match line.find('=') {
+                Some(i) => (line[..i].trim(), unquote(line[i + 1..].trim())),
+                None => return Err(error(format!("expected 'key = value', found '{}'", line))),
+            };
+
+            if key == "preset" {
+                theme = Theme::preset(value)
+                    .ok_or_else(|| error(format!("unknown preset '{}'", value)))?;
+                continue;
+            }
+            let (state, part) = match key.find('.') {
+                Some(i) => (&key[..i], &key[i + 1..]),
+                None => (key, "glyph"),
+            };
+            let cell_state = match state {
+                "alive" => Some(CellState::Alive),
+                "dead" => Some(CellState::Dead),
+                "born" => Some(CellState::Born),
+                "died" => Some(CellState::Died),
+                "none" => None,
+                _ => return Err(error(format!("unknown cell state '{}'", state))),
+            };
+            let colour = || -> Result<Option<Colour>, ThemeError> {
+                if value == "default" {
+                    Ok(None)
+                } else {
+                    value.parse().map(Some).map_err(error)
+                }
+            };
+            let style = theme.style_mut(cell_state);
+            match part {
+                "glyph" => style.glyph = value.to_string(),
+                "fg" => style.foreground = colour()?,
+                "bg" => style.background = colour()?,
+                _ => return Err(error(format!("unknown setting '{}'", key))),
+            }
+        }
+        Ok(theme)
+    }
+}
+
+fn unquote(value: &str) -> &str {
+    if value.len() >= 2 && value.starts_with('"') && value.ends_with('"') {
+        &value[1..value.len() - 1]
+    } else {
+        value
+    }
+}
+
+impl FromStr for Theme {
+    type Err = ThemeError;
+
+    fn from_str(s: &str) -> Result<Theme, ThemeError> {
+        Theme::parse(s)
+    }
+}
+
+/// A malformed theme file, with the 1-based line of the problem.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ThemeError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ThemeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for ThemeError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_colours() {
+        assert_eq!("green".parse(), Ok(Colour::GREEN));
+        assert_eq!("Bright-Red".parse(), Ok(Colour::Ansi(9)));
+        assert_eq!("#56B4e9".parse(), Ok(Colour::Rgb(86, 180, 233)));
+        assert!("#56b4".parse::<Colour>().is_err());
+        assert!("#aééa".parse::<Colour>().is_err());
+        assert!("#+f+f+f".parse::<Colour>().is_err());
+        assert!("mauve".parse::<Colour>().is_err());
+    }
+
+    #[test]
+    fn styles_are_wrapped_in_their_colours() {
+        let style = Style::new("O")
+            .with_foreground(Colour::GREEN)
+            .with_background(Colour::Rgb(1, 2, 3));
+
+        assert_eq!(
+            style.to_terminal_string(),
+            format!(
+                "{}{}O{}{}",
+                color::Fg(color::Green),
+                color::Bg(color::Rgb(1, 2, 3)),
+                color::Fg(color::Reset),
+                color::Bg(color::Reset)
+            )
+        );
+        assert_eq!(Style::new("o").to_terminal_string(), "o");
+    }
+
+    #[test]
+    fn presets_are_found_by_name() {
+        assert_eq!(Theme::preset("monochrome"), Some(Theme::monochrome()));
+        assert_eq!(
+            Theme::preset("colour-blind"),
+            Some(Theme::colour_blind_safe())
+        );
+        assert_eq!(Theme::preset("disco"), None);
+        assert_eq!(Theme::default(), Theme::classic());
+    }
+
+    #[test]
+    fn the_builder_replaces_single_styles() {
+        let theme = Theme::monochrome().with_style(Some(CellState::Alive), Style::new("#"));
+
+        assert_eq!(theme.style(Some(CellState::Alive)).glyph, "#");
+        assert_eq!(theme.style(None), &Style::new("x"));
+    }
+
+    #[test]
+    fn reads_theme_files() {
+        let theme: Theme = "\
+# A comment
+preset = monochrome
+born = \"@\"
+born.fg = #56b4e9
+dead = \" \"
+dead.bg = black
+none.fg = bright-black
+"
+        .parse()
+        .unwrap();
+
+        assert_eq!(
+            theme,
+            Theme::monochrome()
+                .with_style(
+                    Some(CellState::Born),
+                    Style::new("@").with_foreground(Colour::Rgb(86, 180, 233))
+                )
+                .with_style(
+                    Some(CellState::Dead),
+                    Style::new(" ").with_background(Colour::BLACK)
+                )
+                .with_style(None, Style::new("x").with_foreground(Colour::Ansi(8)))
+        );
+    }
+
+    #[test]
+    fn colours_can_be_reset_to_the_terminal_default() {
+        let theme = Theme::parse("born.fg = default").unwrap();
+
+        assert_eq!(theme.born, Style::new("O"));
+    }
+
+    #[test]
+    fn rejects_bad_theme_files() {
+        assert_eq!(
+            Theme::parse("alive = o\nalive.fg = mauve"),
+            Err(ThemeError {
+                line: 2,
+                message: String::from("unknown colour 'mauve'"),
+            })
+        );
+        assert!(Theme::parse("preset = disco").is_err());
+        assert!(Theme::parse("undead = z").is_err());
+        assert!(Theme::parse("alive.size = 3").is_err());
+        assert!(Theme::parse("alive").is_err());
+    }
+}